@@ -0,0 +1,49 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Ancestors', () => {
+  test('returns ancestors from the parent up to the root', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+    const c = b.newNamedChild('c');
+
+    expect(tree.getAncestorIds(c.id)).toEqual([b.id, a.id, root.id]);
+    expect(c.ancestors.map(v => v.id)).toEqual([b.id, a.id, root.id]);
+    expect(tree.getAncestorIds(root.id)).toEqual([]);
+  });
+
+  test('a deleted vertex has no path to the root', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+
+    a.delete();
+
+    const ancestorIds = tree.getAncestorIds(b.id);
+    expect(ancestorIds[0]).toBe(a.id);
+    expect(ancestorIds).not.toContain(root.id);
+  });
+
+  test('returns an empty array for an unknown vertex', () => {
+    const tree = new RepTree('peer1');
+    tree.createRoot();
+
+    expect(tree.getAncestorIds('missing')).toEqual([]);
+    expect(tree.getAncestors('missing')).toEqual([]);
+  });
+
+  test('throws when the ancestors form a cycle', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+
+    // Bypass the move CRDT to corrupt the state on purpose
+    (tree as any).state.moveVertex(a.id, b.id);
+
+    expect(() => tree.getAncestorIds(b.id)).toThrowError(/Cycle/);
+  });
+});
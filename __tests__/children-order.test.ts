@@ -0,0 +1,36 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Children order', () => {
+  test('children with the same creation date are listed in the same order on every replica', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = root.newNamedChild('b');
+    const c = root.newNamedChild('c');
+
+    const sameDate = new Date('2024-01-01T00:00:00.000Z').toISOString();
+    for (const vertex of [c, a, b]) {
+      tree.setVertexProperty(vertex.id, '_c', sameDate);
+    }
+
+    const ops = tree.getAllOps();
+    const replica1 = new RepTree('peer2', [...ops].reverse());
+    const half = Math.floor(ops.length / 2);
+    const replica2 = new RepTree('peer3', [...ops.slice(half), ...ops.slice(0, half)]);
+
+    const expected = [c.id, a.id, b.id];
+    expect(tree.getChildren(root.id).map(v => v.id)).toEqual(expected);
+    expect(replica1.getChildren(root.id).map(v => v.id)).toEqual(expected);
+    expect(replica2.getChildren(root.id).map(v => v.id)).toEqual(expected);
+  });
+
+  test('children created in the same millisecond keep their creation order', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const ids = Array.from({ length: 20 }, (_, i) => root.newNamedChild(`child ${i}`).id);
+
+    expect(tree.getChildren(root.id).map(v => v.id)).toEqual(ids);
+    expect(new RepTree('peer2', [...tree.getAllOps()].reverse()).getChildren(root.id).map(v => v.id)).toEqual(ids);
+  });
+});
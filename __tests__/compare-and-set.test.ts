@@ -0,0 +1,34 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Compare-and-set properties', () => {
+  test('sets the property when the expected value matches', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const task = root.newNamedChild('task', { status: 'todo', tags: ['a'] });
+    tree.popLocalOps();
+
+    expect(tree.compareAndSetVertexProperty(task.id, 'status', 'todo', 'in-progress')).toBe(true);
+    expect(tree.compareAndSetVertexProperty(task.id, 'tags', ['a'], ['a', 'b'])).toBe(true);
+    expect(tree.compareAndSetVertexProperty(task.id, 'assignee', undefined, 'alice')).toBe(true);
+
+    expect(task.getProperty('status')).toBe('in-progress');
+    expect(task.getProperty('tags')).toEqual(['a', 'b']);
+    expect(task.getProperty('assignee')).toBe('alice');
+    expect(tree.popLocalOps().length).toBe(3);
+  });
+
+  test("doesn't emit an op when the expected value doesn't match", () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const task = root.newNamedChild('task', { status: 'done' });
+    tree.popLocalOps();
+
+    expect(tree.compareAndSetVertexProperty(task.id, 'status', 'todo', 'in-progress')).toBe(false);
+    expect(tree.compareAndSetVertexProperty(task.id, 'status', undefined, 'in-progress')).toBe(false);
+
+    expect(task.getProperty('status')).toBe('done');
+    expect(tree.popLocalOps()).toEqual([]);
+    expect(() => tree.compareAndSetVertexProperty('missing', 'status', undefined, 'todo')).toThrowError(/not found/);
+  });
+});
@@ -0,0 +1,42 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+import { checkConvergence, createRandom, generateConcurrentOps, shuffle } from './utils/convergence-utils';
+
+describe('Convergence under different op orders', () => {
+  const seeds = [1, 2, 3, 4, 5, 6, 7, 8];
+
+  for (const seed of seeds) {
+    test(`replicas converge for seed ${seed}`, () => {
+      const random = createRandom(seed);
+      const ops = generateConcurrentOps(random, 3, 60);
+
+      const failure = checkConvergence(ops, seed);
+      if (failure) {
+        console.error(`Seed ${seed}: ${failure.reason}. Shrunk to ${failure.ops.length} ops:`, JSON.stringify(failure.ops));
+      }
+      expect(failure).toBeNull();
+    });
+  }
+
+  test('state vectors match when a property op loses to a newer one', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    root.setProperty('title', 'First');
+    root.setProperty('title', 'Second');
+
+    const ops = tree.getAllOps();
+    const a = new RepTree('observer', ops);
+    const b = new RepTree('observer', [...ops].reverse());
+
+    expect(b.getVertexProperty(root.id, 'title')).toBe('Second');
+    expect(b.getStateVector()).toEqual(a.getStateVector());
+  });
+
+  test('the generator is deterministic for a seed', () => {
+    const opsA = generateConcurrentOps(createRandom(42), 2, 20);
+    const opsB = generateConcurrentOps(createRandom(42), 2, 20);
+
+    expect(opsA.length).toBe(opsB.length);
+    expect(shuffle([1, 2, 3, 4, 5], createRandom(7))).toEqual(shuffle([1, 2, 3, 4, 5], createRandom(7)));
+  });
+});
@@ -0,0 +1,40 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, type Vertex } from '../src';
+
+describe('Subtree deletion', () => {
+  function buildBalancedTree(parent: Vertex, depth: number, fanOut: number, created: Vertex[]) {
+    if (depth === 0) return;
+    for (let i = 0; i < fanOut; i++) {
+      const child = parent.newNamedChild(`${parent.name ?? 'node'}-${i}`);
+      created.push(child);
+      buildBalancedTree(child, depth - 1, fanOut, created);
+    }
+  }
+
+  test('deleting a vertex removes its whole subtree with a single op', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const subtreeRoot = root.newNamedChild('subtree');
+    const descendants: Vertex[] = [];
+    buildBalancedTree(subtreeRoot, 3, 3, descendants);
+    const keep = root.newNamedChild('keep');
+
+    expect(descendants.length).toBe(3 + 9 + 27);
+
+    tree.popLocalOps();
+    subtreeRoot.delete();
+    expect(tree.popLocalOps().length).toBe(1);
+
+    expect(root.childrenIds).toEqual([keep.id]);
+    for (const vertex of [subtreeRoot, ...descendants]) {
+      expect(tree.isAncestor(vertex.id, root.id)).toBe(false);
+    }
+
+    // The structure of the deleted subtree stays intact
+    expect(subtreeRoot.childrenIds.length).toBe(3);
+
+    const replica = tree.replicate('peer2');
+    expect(replica.compareStructure(tree)).toBe(true);
+    expect(replica.getChildrenIds(root.id)).toEqual([keep.id]);
+  });
+});
@@ -0,0 +1,42 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Descendants', () => {
+  function buildTree() {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = root.newNamedChild('b');
+    const a1 = a.newNamedChild('a1');
+    const a2 = a.newNamedChild('a2');
+    const b1 = b.newNamedChild('b1');
+    const a1x = a1.newNamedChild('a1x');
+    return { tree, root, a, b, a1, a2, b1, a1x };
+  }
+
+  test('returns every descendant with its depth in breadth-first order', () => {
+    const { tree, root } = buildTree();
+
+    const descendants = tree.getDescendants(root.id).map(d => [d.vertex.name, d.depth]);
+    expect(descendants).toEqual([
+      ['a', 0], ['b', 0],
+      ['a1', 1], ['a2', 1], ['b1', 1],
+      ['a1x', 2],
+    ]);
+  });
+
+  test('respects the depth limit', () => {
+    const { tree, root, a } = buildTree();
+
+    expect(tree.getDescendants(root.id, 0).map(d => d.vertex.name)).toEqual(['a', 'b']);
+    expect(tree.getDescendants(root.id, 1).map(d => d.vertex.name)).toEqual(['a', 'b', 'a1', 'a2', 'b1']);
+    expect(tree.getDescendants(a.id, 5).map(d => d.vertex.name)).toEqual(['a1', 'a2', 'a1x']);
+  });
+
+  test('returns nothing for a leaf or an unknown vertex', () => {
+    const { tree, a1x } = buildTree();
+
+    expect(tree.getDescendants(a1x.id)).toEqual([]);
+    expect(tree.getDescendants('missing')).toEqual([]);
+  });
+});
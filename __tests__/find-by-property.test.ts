@@ -0,0 +1,59 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Finding vertices by property', () => {
+  function buildTree() {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    root.setProperties({ type: 'folder', name: 'Root' });
+    const docs = root.newNamedChild('Docs', { type: 'folder' });
+    const readme = docs.newNamedChild('README.md', { type: 'file', meta: { size: 1 } });
+    const nested = docs.newNamedChild('Nested', { type: 'folder' });
+    const logo = nested.newNamedChild('logo.png', { type: 'file', meta: { size: 2 } });
+    const note = root.newNamedChild('note');
+    return { tree, root, docs, readme, nested, logo, note };
+  }
+
+  test('finds all vertices with a given value', () => {
+    const { tree, root, docs, nested, readme, logo } = buildTree();
+
+    expect(tree.findVerticesWithProperty('type', 'folder').map(v => v.id)).toEqual([root.id, docs.id, nested.id]);
+    expect(tree.findVerticesWithProperty('type', 'file').map(v => v.id)).toEqual([readme.id, logo.id]);
+    expect(tree.findVerticesWithProperty('meta', { size: 2 }).map(v => v.id)).toEqual([logo.id]);
+    expect(tree.findVerticesWithProperty('type', 'image')).toEqual([]);
+  });
+
+  test('finds all vertices having a key when no value is given', () => {
+    const { tree, note } = buildTree();
+
+    const withType = tree.findVerticesWithProperty('type');
+    expect(withType).toHaveLength(5);
+    expect(withType.map(v => v.id)).not.toContain(note.id);
+  });
+
+  test('skips deleted vertices', () => {
+    const { tree, nested, logo } = buildTree();
+    nested.delete();
+
+    const ids = tree.findVerticesWithProperty('type').map(v => v.id);
+    expect(ids).not.toContain(nested.id);
+    expect(ids).not.toContain(logo.id);
+  });
+
+  test('ignores transient values unless asked to include them', () => {
+    const { tree, readme, note } = buildTree();
+    tree.setTransientVertexProperty(note.id, 'type', 'file');
+    tree.setTransientVertexProperty(readme.id, 'type', 'draft');
+
+    expect(tree.findVerticesWithProperty('type', 'file').map(v => v.id)).not.toContain(note.id);
+    expect(tree.findVerticesWithProperty('type', 'file').map(v => v.id)).toContain(readme.id);
+    expect(tree.findVerticesWithProperty('type', 'draft')).toEqual([]);
+
+    expect(tree.findVerticesWithProperty('type', 'file', true).map(v => v.id)).toContain(note.id);
+    expect(tree.findVerticesWithProperty('type', 'draft', true).map(v => v.id)).toEqual([readme.id]);
+  });
+
+  test('returns nothing for a tree without a root', () => {
+    expect(new RepTree('peer1').findVerticesWithProperty('type')).toEqual([]);
+  });
+});
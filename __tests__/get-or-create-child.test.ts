@@ -0,0 +1,36 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Get or create a child', () => {
+  test('returns the same child when called twice with the same arguments', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const other = root.newNamedChild('other');
+
+    const docs = tree.getOrCreateChild(root.id, 'name', 'docs');
+    const opsCount = tree.getAllOps().length;
+
+    expect(tree.getOrCreateChild(root.id, 'name', 'docs').id).toBe(docs.id);
+    expect(tree.getAllOps().length).toBe(opsCount);
+    expect(root.childrenIds).toEqual([other.id, docs.id]);
+    expect(docs.name).toBe('docs');
+
+    expect(tree.getOrCreateChild(root.id, 'name', 'other').id).toBe(other.id);
+    expect(() => tree.getOrCreateChild('missing', 'name', 'docs')).toThrowError(/not found/);
+  });
+
+  test('replicas agree on the child after concurrent creation', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const tree2 = tree1.replicate('peer2');
+
+    tree1.getOrCreateChild(root.id, 'name', 'docs');
+    tree2.getOrCreateChild(root.id, 'name', 'docs');
+    tree1.syncWith(tree2);
+
+    const docs1 = tree1.getOrCreateChild(root.id, 'name', 'docs');
+    const docs2 = tree2.getOrCreateChild(root.id, 'name', 'docs');
+    expect(docs1.id).toBe(docs2.id);
+    expect(tree1.getChildren(root.id).length).toBe(2);
+  });
+});
@@ -0,0 +1,52 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Vertex IDs', () => {
+  function buildTreeWithCounterIds(): string[] {
+    let counter = 0;
+    const tree = new RepTree('peer1');
+    tree.idGenerator = () => `v${++counter}`;
+
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    a.newNamedChild('b');
+    root.newChild();
+
+    return tree.getAllVertices().map(v => v.id);
+  }
+
+  test('a custom generator produces reproducible IDs', () => {
+    const firstRun = buildTreeWithCounterIds();
+    const secondRun = buildTreeWithCounterIds();
+
+    expect(firstRun).toEqual(secondRun);
+    expect(firstRun).toEqual(expect.arrayContaining(['v1', 'v2', 'v3', 'v4']));
+  });
+
+  test('creates a vertex with a supplied ID', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const vertex = tree.newVertexWithId('custom-id', root.id, { name: 'custom' });
+
+    expect(vertex.id).toBe('custom-id');
+    expect(tree.getVertex('custom-id')?.name).toBe('custom');
+    expect(root.childrenIds).toEqual(['custom-id']);
+
+    expect(() => tree.newVertexWithId('custom-id', root.id)).toThrowError(/already exists/);
+  });
+
+  test('throws when the generator returns an ID that is already taken', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const existing = root.newNamedChild('existing');
+    tree.popLocalOps();
+
+    tree.idGenerator = () => existing.id;
+
+    expect(() => root.newNamedChild('other')).toThrowError(/already exists/);
+    expect(() => root.newChild()).toThrowError(/already exists/);
+    expect(existing.name).toBe('existing');
+    expect(existing.parentId).toBe(root.id);
+    expect(tree.popLocalOps()).toEqual([]);
+  });
+});
@@ -0,0 +1,53 @@
+import { describe, test, expect, vi } from 'vitest';
+import { RepTree, isMoveVertexOp } from '../src';
+
+describe('Integrity check', () => {
+  test('a tree built by the CRDT has no problems', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    a.newNamedChild('b').delete();
+
+    expect(tree.checkIntegrity()).toEqual({ cycles: [], orphans: [], missingVertices: [] });
+  });
+
+  test('reports a cycle and an orphan in a corrupted state', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+    const c = b.newNamedChild('c');
+
+    // Bypass the move CRDT to corrupt the state on purpose
+    const errorSpy = vi.spyOn(console, 'error').mockImplementation(() => {});
+    (tree as any).state.moveVertex(a.id, b.id);
+    (tree as any).state.moveVertex('orphan', 'missing-parent');
+    errorSpy.mockRestore();
+
+    const report = tree.checkIntegrity();
+
+    expect(report.orphans).toEqual(['orphan']);
+    expect(report.cycles.length).toBe(1);
+    expect([...report.cycles[0]].sort()).toEqual([a.id, b.id].sort());
+    // c hangs off the cycle but isn't part of it
+    expect(report.cycles[0]).not.toContain(c.id);
+  });
+
+  test('reports vertices referenced by ops that arrived before them', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const tree2 = tree1.replicate('peer2');
+    const folder = tree2.getVertex(root.id)!.newNamedChild('folder');
+    folder.newNamedChild('doc');
+
+    // Hold back the op that creates the folder
+    const ops = tree2.popLocalOps();
+    const folderCreation = ops.find(op => isMoveVertexOp(op) && op.targetId === folder.id)!;
+    tree1.merge(ops.filter(op => op !== folderCreation));
+
+    expect(tree1.checkIntegrity().missingVertices).toEqual([folder.id]);
+
+    tree1.merge([folderCreation]);
+    expect(tree1.checkIntegrity()).toEqual({ cycles: [], orphans: [], missingVertices: [] });
+  });
+});
@@ -0,0 +1,71 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('JSON export', () => {
+  test('exports a subtree as nested JSON', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    root.name = 'Project';
+    const docs = root.newNamedChild('Docs', { type: 'folder' });
+    const readme = docs.newNamedChild('README.md', { size: 2048, meta: { tags: ['a', 'b'] } });
+    const images = root.newNamedChild('Images', { type: 'folder', hidden: false, icon: null });
+
+    const json = tree.exportJson(root.id)!;
+
+    expect(json.id).toBe(root.id);
+    expect(json.properties.name).toBe('Project');
+    expect(json.children.map(c => c.id)).toEqual([docs.id, images.id]);
+
+    const docsJson = json.children[0];
+    expect(docsJson.properties).toMatchObject({ name: 'Docs', type: 'folder' });
+    expect(docsJson.children).toHaveLength(1);
+    expect(docsJson.children[0]).toEqual({
+      id: readme.id,
+      properties: {
+        _c: readme.getProperty('_c'),
+        name: 'README.md',
+        size: 2048,
+        meta: { tags: ['a', 'b'] },
+      },
+      children: [],
+    });
+
+    expect(json.children[1].properties).toMatchObject({ hidden: false, icon: null });
+    expect(json.children[1].children).toEqual([]);
+
+    // The result is plain JSON
+    expect(JSON.parse(JSON.stringify(json))).toEqual(json);
+  });
+
+  test('skips transient properties', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    root.setProperty('title', 'Saved');
+    root.setTransientProperty('title', 'Draft');
+
+    expect(tree.exportJson(root.id)!.properties.title).toBe('Saved');
+  });
+
+  test('handles deep trees', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    let current = root;
+    for (let i = 0; i < 5000; i++) {
+      current = current.newChild();
+    }
+
+    let json = tree.exportJson(root.id)!;
+    let depth = 0;
+    while (json.children.length > 0) {
+      json = json.children[0];
+      depth++;
+    }
+    expect(depth).toBe(5000);
+    expect(json.id).toBe(current.id);
+  });
+
+  test('returns undefined for an unknown vertex', () => {
+    const tree = new RepTree('peer1');
+    expect(tree.exportJson('missing')).toBeUndefined();
+  });
+});
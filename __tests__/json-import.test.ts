@@ -0,0 +1,64 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, type VertexJson } from '../src';
+
+function withoutIds(json: VertexJson): unknown {
+  return {
+    properties: json.properties,
+    children: json.children.map(withoutIds),
+  };
+}
+
+describe('JSON import', () => {
+  test('round-trips a multi-level document with exportJson', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const doc = root.newNamedChild('Doc', { meta: { tags: ['x', 'y'], flags: { draft: true } } });
+    const section1 = doc.newNamedChild('Section 1', { order: 1 });
+    section1.newNamedChild('Paragraph', { text: 'Hello', list: [1, 2, 3] });
+    section1.newNamedChild('Paragraph', { text: 'World', empty: null });
+    doc.newNamedChild('Section 2', { order: 2 });
+
+    const exported = tree.exportJson(doc.id)!;
+
+    const target = root.newNamedChild('Copies');
+    const imported = tree.importJson(target.id, exported);
+
+    expect(imported.parentId).toBe(target.id);
+    expect(imported.id).not.toBe(doc.id);
+
+    const reExported = tree.exportJson(imported.id)!;
+    expect(withoutIds(reExported)).toEqual(withoutIds(exported));
+  });
+
+  test('imports JSON without ids and creation dates', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+
+    const imported = tree.importJson(root.id, {
+      properties: { name: 'Folder' },
+      children: [
+        { properties: { name: 'A' } },
+        { properties: { name: 'B' }, children: [{}] },
+        { properties: { name: 'C' } },
+      ],
+    });
+
+    expect(imported.name).toBe('Folder');
+    expect(imported.children.map(c => c.name)).toEqual(['A', 'B', 'C']);
+    expect(imported.children[1].childrenIds).toHaveLength(1);
+  });
+
+  test('rejects malformed JSON without importing anything', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+
+    expect(() => tree.importJson(root.id, { children: 'nope' })).toThrowError(/children/);
+    expect(() => tree.importJson(root.id, { properties: [] })).toThrowError(/properties/);
+    expect(() => tree.importJson(root.id, { children: [{ children: [42] }] })).toThrowError();
+    expect(() => tree.importJson(root.id, { properties: { when: new Date() } })).toThrowError();
+    expect(() => tree.importJson(root.id, null)).toThrowError();
+    expect(() => tree.importJson('missing', {})).toThrowError();
+
+    expect(root.childrenIds).toEqual([]);
+  });
+});
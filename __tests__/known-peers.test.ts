@@ -0,0 +1,28 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Known peers', () => {
+  test('a tree merged from three peers reports all of them', () => {
+    const origin = new RepTree('origin');
+    const root = origin.createRoot();
+
+    const peers = ['carol', 'alice', 'bob'].map(peerId => origin.replicate(peerId));
+    peers[0].newVertex(root.id);
+    peers[1].setVertexProperty(root.id, 'title', 'Hello');
+    peers[2].setTransientVertexProperty(root.id, 'cursor', 1);
+
+    // A fresh replica only knows the origin
+    const observer = origin.replicate('observer');
+    expect(observer.getKnownPeerIds()).toEqual(['origin']);
+
+    for (const peer of peers) {
+      observer.merge(peer.getAllOps());
+    }
+    // bob only sent a transient property, so there's nothing from bob in the tree yet
+    expect(observer.getKnownPeerIds()).toEqual(['alice', 'carol', 'origin']);
+
+    peers[2].setVertexProperty(root.id, 'title', 'Hi');
+    observer.merge(peers[2].getAllOps());
+    expect(observer.getKnownPeerIds()).toEqual(['alice', 'bob', 'carol', 'origin']);
+  });
+});
@@ -56,4 +56,29 @@ describe('LWW Properties', () => {
   test('should handle object properties with LWW semantics', () => {
     testPropertyType('meta', [{}, {a:1}, {a:1,b:2}, {nested:{x:1}}], {nested:{x:1}});
   });
+
+  test('should converge on concurrent set and unset', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    root.setProperty('title', 'Draft');
+    const tree2 = tree1.replicate('peer2');
+
+    // Both ops get the same counter, so the peer ID decides
+    tree1.unsetVertexProperty(root.id, 'title');
+    tree2.setVertexProperty(root.id, 'title', 'Final');
+    tree1.merge(tree2.getAllOps());
+    tree2.merge(tree1.getAllOps());
+
+    expect(tree1.getVertexProperty(root.id, 'title')).toBe('Final');
+    expect(tree2.getVertexProperty(root.id, 'title')).toBe('Final');
+
+    // A later unset wins and removes the key everywhere
+    tree1.unsetVertexProperty(root.id, 'title');
+    tree2.merge(tree1.getAllOps());
+
+    for (const tree of [tree1, tree2]) {
+      expect(tree.getVertexProperty(root.id, 'title')).toBeUndefined();
+      expect(tree.exportJson(root.id)!.properties).not.toHaveProperty('title');
+    }
+  });
 });
@@ -0,0 +1,44 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Missing ranges', () => {
+  test('explains which ranges getMissingOps sends', () => {
+    // peerA: 1..2 - null vertex and its _c, 3..4 - root and its _c, 5..11 - properties
+    const tree = new RepTree('peerA');
+    const root = tree.createRoot();
+    for (let i = 0; i < 7; i++) {
+      root.setProperty('count', i);
+    }
+
+    // peerB: 1..2 - null vertex and its _c
+    tree.merge(new RepTree('peerB').getAllOps());
+
+    expect(tree.getStateVector()).toEqual({
+      peerA: [[1, 11]],
+      peerB: [[1, 2]],
+    });
+
+    const theirStateVector = { peerA: [[1, 3], [6, 7]] };
+    const missingRanges = tree.getMissingRanges(theirStateVector);
+
+    expect(missingRanges).toEqual([
+      { peerId: 'peerA', start: 4, end: 5 },
+      { peerId: 'peerA', start: 8, end: 11 },
+      { peerId: 'peerB', start: 1, end: 2 },
+    ]);
+
+    const missingOps = tree.getMissingOps(theirStateVector);
+    expect(missingOps.length).toBe(8);
+    expect(missingOps.map(op => `${op.id.counter}@${op.id.peerId}`)).toEqual([
+      '1@peerB', '2@peerB', '4@peerA', '5@peerA', '8@peerA', '9@peerA', '10@peerA', '11@peerA',
+    ]);
+  });
+
+  test('returns null when state vectors are disabled', () => {
+    const tree = new RepTree('peerA');
+    tree.createRoot();
+    tree.stateVectorEnabled = false;
+
+    expect(tree.getMissingRanges({})).toBeNull();
+  });
+});
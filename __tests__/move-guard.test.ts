@@ -0,0 +1,89 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Move guards', () => {
+  test('a guard forbids moving locked vertices', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const folder = root.newNamedChild('folder');
+    const locked = root.newNamedChild('locked', { locked: true });
+    const unlocked = root.newNamedChild('unlocked');
+    tree.popLocalOps();
+
+    const removeGuard = tree.addMoveGuard(vertexId => tree.getVertexProperty(vertexId, 'locked') !== true);
+
+    expect(() => tree.moveVertex(locked.id, folder.id)).toThrowError(/rejected/);
+    expect(() => locked.delete()).toThrowError(/rejected/);
+    expect(locked.parentId).toBe(root.id);
+    expect(tree.popLocalOps()).toEqual([]);
+
+    unlocked.moveTo(folder);
+    expect(unlocked.parentId).toBe(folder.id);
+
+    removeGuard();
+    tree.moveVertex(locked.id, folder.id);
+    expect(locked.parentId).toBe(folder.id);
+  });
+
+  test('moves from other peers are not checked', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const folder = root.newNamedChild('folder');
+    const locked = root.newNamedChild('locked', { locked: true });
+    const tree2 = tree1.replicate('peer2');
+
+    tree1.addMoveGuard(vertexId => tree1.getVertexProperty(vertexId, 'locked') !== true);
+    tree2.moveVertex(locked.id, folder.id);
+    tree1.merge(tree2.getAllOps());
+
+    expect(tree1.getVertex(locked.id)?.parentId).toBe(folder.id);
+    expect(tree1.compareStructure(tree2)).toBe(true);
+  });
+
+  test('an undo that moves a locked vertex is rejected without emitting ops', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const folder = root.newNamedChild('folder');
+    const doc = root.newNamedChild('doc');
+    tree1.addMoveGuard(vertexId => tree1.getVertexProperty(vertexId, 'locked') !== true);
+
+    doc.moveTo(folder);
+    tree1.popLocalOps();
+
+    // The doc gets locked by another peer
+    const tree2 = tree1.replicate('peer2');
+    tree2.setVertexProperty(doc.id, 'locked', true);
+    tree1.merge(tree2.getAllOps());
+
+    expect(() => tree1.undo()).toThrowError(/rejected/);
+    expect(doc.parentId).toBe(folder.id);
+    expect(tree1.popLocalOps()).toEqual([]);
+    // The edit stays on the undo stack
+    expect(tree1.canUndo).toBe(true);
+
+    tree2.setVertexProperty(doc.id, 'locked', false);
+    tree1.merge(tree2.getAllOps());
+    expect(tree1.undo()).toBe(true);
+    expect(doc.parentId).toBe(root.id);
+  });
+
+  test('a guard forbids creating children under a locked vertex', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const locked = root.newNamedChild('locked', { locked: true });
+    tree.popLocalOps();
+
+    tree.addMoveGuard((_vertexId, parentId) => tree.getVertexProperty(parentId, 'locked') !== true);
+
+    expect(() => locked.newNamedChild('child')).toThrowError(/rejected/);
+    expect(() => locked.newChild()).toThrowError(/rejected/);
+    expect(() => tree.newVertexWithId('custom-id', locked.id)).toThrowError(/rejected/);
+    expect(() => tree.getOrCreateChild(locked.id, 'name', 'child')).toThrowError(/rejected/);
+    expect(() => tree.importJson(locked.id, { properties: { name: 'child' } })).toThrowError(/rejected/);
+    expect(locked.childrenIds).toEqual([]);
+    expect(tree.popLocalOps()).toEqual([]);
+
+    root.newNamedChild('allowed');
+    expect(root.children.map(c => c.name)).toContain('allowed');
+  });
+});
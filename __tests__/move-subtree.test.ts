@@ -0,0 +1,59 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, equalsOpId, isMoveVertexOp } from '../src';
+
+describe('Subtree moves', () => {
+  test('moves a vertex together with its descendants', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+    const c = b.newNamedChild('c');
+    const target = root.newNamedChild('target');
+    tree.popLocalOps();
+
+    const opId = tree.moveSubtree(a.id, target.id);
+
+    const ops = tree.popLocalOps();
+    expect(ops.length).toBe(1);
+    expect(equalsOpId(ops[0].id, opId)).toBe(true);
+    expect(tree.getAncestorIds(c.id)).toEqual([b.id, a.id, target.id, root.id]);
+  });
+
+  test('rejects moving a subtree under one of its own descendants', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = a.newNamedChild('b');
+    const c = b.newNamedChild('c');
+    tree.popLocalOps();
+
+    expect(() => tree.moveSubtree(a.id, c.id)).toThrowError(/descendant/);
+    expect(() => tree.moveSubtree(a.id, a.id)).toThrowError(/descendant/);
+
+    expect(tree.popLocalOps()).toEqual([]);
+    expect(a.parentId).toBe(root.id);
+    expect(tree.getAncestorIds(c.id)).toEqual([b.id, a.id, root.id]);
+  });
+
+  test('returns the ID of the move even when a callback emits ops during it', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const target = root.newNamedChild('target');
+    tree.popLocalOps();
+
+    // Stamps every moved vertex, emitting a property op while the move is applied
+    tree.observeOpApplied(op => {
+      if (isMoveVertexOp(op) && op.targetId === a.id) {
+        tree.setVertexProperty(a.id, 'movedAt', op.id.counter);
+      }
+    });
+
+    const opId = tree.moveSubtree(a.id, target.id);
+
+    const ops = tree.popLocalOps();
+    expect(ops.length).toBe(2);
+    const moveOp = ops.find(op => isMoveVertexOp(op))!;
+    expect(equalsOpId(moveOp.id, opId)).toBe(true);
+  });
+});
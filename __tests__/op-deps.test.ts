@@ -0,0 +1,66 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, isAnyPropertyOp, equalsOpId, type SetVertexProperty } from '../src';
+
+describe('Explicit op dependencies', () => {
+  test('an op waits for its dependency before being applied', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const doc = root.newNamedChild('doc');
+    doc.setProperty('body', 'Hello');
+    doc.setProperty('published', true);
+
+    const ops = tree1.getAllOps();
+    const bodyOp = ops.find(op => isAnyPropertyOp(op) && op.key === 'body') as SetVertexProperty;
+    const publishedOp = ops.find(op => isAnyPropertyOp(op) && op.key === 'published') as SetVertexProperty;
+    publishedOp.deps = [bodyOp.id];
+
+    const tree2 = new RepTree('peer2');
+    tree2.merge(ops.filter(op => !equalsOpId(op.id, bodyOp.id)));
+
+    expect(tree2.getVertexProperty(doc.id, 'published')).toBeUndefined();
+
+    tree2.merge([bodyOp]);
+
+    expect(tree2.getVertexProperty(doc.id, 'body')).toBe('Hello');
+    expect(tree2.getVertexProperty(doc.id, 'published')).toBe(true);
+    expect(tree2.compareStructure(tree1)).toBe(true);
+  });
+
+  test('an op with satisfied dependencies is applied right away', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const doc = root.newNamedChild('doc');
+    doc.setProperty('body', 'Hello');
+    doc.setProperty('published', true);
+
+    const ops = tree1.getAllOps();
+    const bodyOp = ops.find(op => isAnyPropertyOp(op) && op.key === 'body') as SetVertexProperty;
+    const publishedOp = ops.find(op => isAnyPropertyOp(op) && op.key === 'published') as SetVertexProperty;
+    publishedOp.deps = [bodyOp.id];
+
+    const tree2 = new RepTree('peer2', ops);
+
+    expect(tree2.getVertexProperty(doc.id, 'published')).toBe(true);
+  });
+
+  test('applies a long chain of dependent ops that arrives in reverse order', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    for (let i = 0; i < 20000; i++) {
+      root.setProperty('count', i);
+    }
+
+    const ops = tree1.getAllOps();
+    const countOps = ops.filter(op => isAnyPropertyOp(op) && op.key === 'count') as SetVertexProperty[];
+    for (let i = 1; i < countOps.length; i++) {
+      countOps[i].deps = [countOps[i - 1].id];
+    }
+
+    const tree2 = new RepTree('peer2');
+    tree2.merge(ops.filter(op => !(isAnyPropertyOp(op) && op.key === 'count')));
+    tree2.merge([...countOps].reverse());
+
+    expect(tree2.getVertexProperty(root.id, 'count')).toBe(19999);
+    expect(tree2.getAllOps().length).toBe(ops.length);
+  });
+});
@@ -0,0 +1,48 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, opIdToString } from '../src';
+
+describe('Paged missing ops', () => {
+  test('pages through a large gap without missing or duplicating ops', () => {
+    const source = new RepTree('source');
+    const root = source.createRoot();
+    const behind = source.replicate('behind');
+
+    for (let i = 0; i < 10000; i++) {
+      source.setVertexProperty(root.id, `prop_${i % 10}`, i);
+    }
+
+    const expectedIds = new Set(source.getMissingOps(behind.getStateVector()!).map(op => opIdToString(op.id)));
+    expect(expectedIds.size).toBe(10000);
+
+    const receivedIds = new Set<string>();
+    let cursor = behind.getStateVector()!;
+    let pages = 0;
+    while (true) {
+      const page = source.getMissingOpsPaged(cursor, 1000);
+      if (page.ops.length === 0) break;
+
+      expect(page.ops.length).toBeLessThanOrEqual(1000);
+      for (const op of page.ops) {
+        const id = opIdToString(op.id);
+        expect(receivedIds.has(id)).toBe(false);
+        receivedIds.add(id);
+      }
+
+      behind.merge(page.ops);
+      cursor = page.cursor;
+      pages++;
+    }
+
+    expect(pages).toBe(10);
+    expect(receivedIds).toEqual(expectedIds);
+    expect(behind.compareStructure(source)).toBe(true);
+    expect(behind.getVertexProperty(root.id, 'prop_9')).toBe(9999);
+  });
+
+  test('rejects a non-positive page size', () => {
+    const tree = new RepTree('peer1');
+    tree.createRoot();
+
+    expect(() => tree.getMissingOpsPaged({}, 0)).toThrowError(/maxOps/);
+  });
+});
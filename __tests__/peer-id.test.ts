@@ -0,0 +1,23 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Peer ID validation', () => {
+  test('rejects an empty peer ID', () => {
+    expect(() => new RepTree('')).toThrowError(/empty/);
+  });
+
+  test('rejects a peer ID with the OpId separator', () => {
+    expect(() => new RepTree('user@example.com')).toThrowError(/@/);
+  });
+
+  test('rejects an invalid peer ID when replicating', () => {
+    const tree = new RepTree('peer1');
+    tree.createRoot();
+
+    expect(() => tree.replicate('')).toThrowError();
+  });
+
+  test('accepts regular peer IDs', () => {
+    expect(new RepTree('peer-1_A').peerId).toBe('peer-1_A');
+  });
+});
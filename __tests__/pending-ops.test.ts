@@ -0,0 +1,73 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, isMoveVertexOp, isAnyPropertyOp } from '../src';
+
+describe('Pending ops', () => {
+  test('a property that arrives before its vertex is applied once the vertex appears', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const folder = root.newNamedChild('Folder');
+    tree1.popLocalOps();
+
+    const tree2 = tree1.replicate('peer2');
+    expect(tree2.getPendingOpsCount()).toBe(0);
+
+    const file = folder.newNamedChild('file.txt');
+    const ops = tree1.popLocalOps();
+    const moveOps = ops.filter(op => isMoveVertexOp(op));
+    const propertyOps = ops.filter(op => isAnyPropertyOp(op));
+
+    tree2.merge(propertyOps);
+    expect(tree2.getVertex(file.id)).toBeUndefined();
+    expect(tree2.getPendingOpsCount()).toBe(propertyOps.length);
+
+    tree2.merge(moveOps);
+    expect(tree2.getPendingOpsCount()).toBe(0);
+    expect(tree2.getVertexProperty(file.id, 'name')).toBe('file.txt');
+    expect(tree2.compareStructure(tree1)).toBe(true);
+  });
+
+  test('a move that arrives before its parent waits for the parent', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    tree1.popLocalOps();
+
+    const tree2 = tree1.replicate('peer2');
+
+    const folder = root.newNamedChild('Folder');
+    const folderOps = tree1.popLocalOps();
+    const file = folder.newNamedChild('file.txt');
+    const fileOps = tree1.popLocalOps();
+
+    tree2.merge(fileOps);
+    expect(tree2.getPendingOpsCount()).toBe(fileOps.length);
+
+    tree2.merge(folderOps);
+    expect(tree2.getPendingOpsCount()).toBe(0);
+    expect(tree2.getVertex(file.id)?.parentId).toBe(folder.id);
+  });
+
+  test('ops re-delivered while pending are applied only once', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    tree1.popLocalOps();
+
+    const tree2 = tree1.replicate('peer2');
+
+    const folder = root.newNamedChild('Folder');
+    const folderOps = tree1.popLocalOps();
+    folder.newNamedChild('file.txt');
+    const fileOps = tree1.popLocalOps();
+
+    tree2.merge(fileOps);
+    tree2.merge(fileOps);
+    expect(tree2.getPendingOpsCount()).toBe(fileOps.length);
+
+    tree2.merge(folderOps);
+    tree2.merge([...folderOps, ...fileOps]);
+
+    expect(tree2.getPendingOpsCount()).toBe(0);
+    expect(tree2.getAllOps().length).toBe(tree1.getAllOps().length);
+    expect(tree2.getStateVector()).toEqual(tree1.getStateVector());
+    expect(tree2.compareStructure(tree1)).toBe(true);
+  });
+});
@@ -0,0 +1,30 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('One-way pull sync', () => {
+  test('a read replica catches up with a primary without sending ops', () => {
+    const primary = new RepTree('primary');
+    const root = primary.createRoot();
+    root.newNamedChild('Docs', { type: 'folder' });
+    primary.popLocalOps();
+
+    const replica = primary.replicate('replica');
+    const primaryOpsCountBefore = primary.getAllOps().length;
+
+    const images = root.newNamedChild('Images', { type: 'folder' });
+    images.newNamedChild('logo.png');
+    root.setProperty('title', 'Project');
+    const newOpsCount = primary.popLocalOps().length;
+
+    expect(replica.pullFrom(primary)).toBe(newOpsCount);
+    expect(replica.compareStructure(primary)).toBe(true);
+    expect(replica.getStateVector()).toEqual(primary.getStateVector());
+
+    // Nothing flowed back to the primary and the replica produced no ops of its own
+    expect(primary.getAllOps().length).toBe(primaryOpsCountBefore + newOpsCount);
+    expect(replica.popLocalOps()).toEqual([]);
+
+    // Pulling again transfers nothing
+    expect(replica.pullFrom(primary)).toBe(0);
+  });
+});
@@ -0,0 +1,54 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Setting a property on many vertices', () => {
+  test('marks 50 tasks as done in one call', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const tasks = Array.from({ length: 50 }, (_, i) => root.newNamedChild(`task-${i}`, { done: false }));
+    tree.popLocalOps();
+
+    const missing = tree.setVertexPropertyForMany(tasks.map(t => t.id), 'done', true);
+
+    expect(missing).toEqual([]);
+    expect(tasks.every(t => t.getProperty('done') === true)).toBe(true);
+    // One op per vertex
+    expect(tree.popLocalOps().length).toBe(50);
+
+    const replica = tree.replicate('peer2');
+    expect(tasks.every(t => replica.getVertexProperty(t.id, 'done') === true)).toBe(true);
+  });
+
+  test('skips and reports missing vertices', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newChild();
+    const b = root.newChild();
+    tree.popLocalOps();
+
+    const missing = tree.setVertexPropertyForMany([a.id, 'missing', b.id], 'label', 'x');
+
+    expect(missing).toEqual(['missing']);
+    expect(a.getProperty('label')).toBe('x');
+    expect(b.getProperty('label')).toBe('x');
+    expect(tree.popLocalOps().length).toBe(2);
+    expect(tree.getPendingOpsCount()).toBe(0);
+  });
+
+  test('throws without setting anything when asked to', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newChild();
+
+    expect(() => tree.setVertexPropertyForMany([a.id, 'missing'], 'label', 'x', true)).toThrowError(/missing/);
+    expect(a.getProperty('label')).toBeUndefined();
+  });
+
+  test('rejects unsupported values', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newChild();
+
+    expect(() => tree.setVertexPropertyForMany([a.id], 'when', new Date() as any)).toThrowError();
+  });
+});
@@ -0,0 +1,126 @@
+import { describe, test, expect } from 'vitest';
+import { StateVector, RepTree } from '../src';
+
+describe('StateVector', () => {
+  test('diff returns all ranges of a peer the other side has no entry for', () => {
+    const ours = new StateVector({
+      peer1: [[1, 5], [8, 10]],
+      peer2: [[1, 3], [7, 7]],
+    });
+    const theirs = new StateVector({
+      peer1: [[1, 10]],
+    });
+
+    expect(ours.diff(theirs)).toEqual([
+      { peerId: 'peer2', start: 1, end: 3 },
+      { peerId: 'peer2', start: 7, end: 7 },
+    ]);
+    expect(ours.diff(new StateVector())).toEqual([
+      { peerId: 'peer1', start: 1, end: 5 },
+      { peerId: 'peer1', start: 8, end: 10 },
+      { peerId: 'peer2', start: 1, end: 3 },
+      { peerId: 'peer2', start: 7, end: 7 },
+    ]);
+  });
+
+  test('diff ignores peers only the other side has', () => {
+    const ours = new StateVector({ peer1: [[1, 3]] });
+    const theirs = new StateVector({ peer1: [[1, 3]], peer2: [[1, 100]] });
+
+    expect(ours.diff(theirs)).toEqual([]);
+  });
+
+  test('contains checks counters inside, at the boundaries of and outside the ranges', () => {
+    const stateVector = new StateVector({ peer1: [[1, 5], [10, 12]] });
+
+    for (const counter of [1, 3, 5, 10, 11, 12]) {
+      expect(stateVector.contains({ peerId: 'peer1', counter })).toBe(true);
+    }
+    for (const counter of [0, 6, 9, 13]) {
+      expect(stateVector.contains({ peerId: 'peer1', counter })).toBe(false);
+    }
+    expect(stateVector.contains({ peerId: 'peer2', counter: 1 })).toBe(false);
+  });
+
+  test('hasOp tells applied ops from unknown and pending ones', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    tree1.popLocalOps();
+    const tree2 = tree1.replicate('peer2');
+
+    const folder = root.newNamedChild('folder');
+    const folderOps = tree1.popLocalOps();
+    folder.newNamedChild('file');
+    const fileOps = tree1.popLocalOps();
+
+    expect(tree2.hasOp(tree1.getAllOps()[0].id)).toBe(true);
+    expect(tree2.hasOp(folderOps[0].id)).toBe(false);
+
+    // Waits for the folder
+    tree2.merge(fileOps);
+    expect(tree2.hasOp(fileOps[0].id)).toBe(false);
+
+    tree2.merge(folderOps);
+    expect([...folderOps, ...fileOps].every(op => tree2.hasOp(op.id))).toBe(true);
+  });
+
+  test('diffSummary counts the missing ops per peer', () => {
+    const ours = new StateVector({
+      peer1: [[1, 10], [20, 29]],
+      peer2: [[1, 5]],
+      peer3: [[1, 3]],
+    });
+
+    // Overlapping, disjoint and identical ranges
+    const theirs = new StateVector({
+      peer1: [[5, 25]],
+      peer2: [[10, 20]],
+      peer3: [[1, 3]],
+    });
+
+    expect(ours.diffSummary(theirs)).toEqual({ total: 4 + 4 + 5, byPeer: { peer1: 8, peer2: 5 } });
+    expect(theirs.diffSummary(ours)).toEqual({ total: 9 + 11, byPeer: { peer1: 9, peer2: 11 } });
+    expect(ours.diffSummary(ours.clone())).toEqual({ total: 0, byPeer: {} });
+    expect(ours.diffSummary(new StateVector())).toEqual({ total: 20 + 5 + 3, byPeer: { peer1: 20, peer2: 5, peer3: 3 } });
+  });
+
+  test('encodes and decodes 50 peers with many ranges', () => {
+    const stateVector = new StateVector();
+    for (let p = 0; p < 50; p++) {
+      const peerId = `peer-${p}-${'x'.repeat(p % 7)}`;
+      // Overlapping runs of counters that merge into fewer ranges, with gaps between them
+      for (let start = 1; start < 200; start += 10 + (p % 5)) {
+        for (let counter = start; counter < start + 6; counter++) stateVector.update(peerId, counter);
+        for (let counter = start + 3; counter < start + 8; counter++) stateVector.update(peerId, counter);
+      }
+    }
+    stateVector.update('peer-big', 2 ** 40);
+    stateVector.update('пир', 1);
+
+    const encoded = stateVector.encode();
+    const decoded = StateVector.decode(encoded);
+
+    expect(decoded.getState()).toEqual(stateVector.getState());
+    expect(encoded.length).toBeLessThan(JSON.stringify(stateVector.getState()).length / 2);
+  });
+
+  test('decoding rejects malformed data', () => {
+    const encoded = new StateVector({ peer1: [[1, 5], [8, 10]] }).encode();
+
+    expect(() => StateVector.decode(encoded.subarray(0, encoded.length - 1))).toThrowError(/Invalid encoded state vector/);
+    expect(() => StateVector.decode(new Uint8Array([...encoded, 0]))).toThrowError(/Invalid encoded state vector/);
+    expect(() => new StateVector({ peer1: [[5, 8], [1, 3]] }).encode()).toThrowError(/overlapping/);
+  });
+
+  test('getMissingOps accepts an encoded state vector', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const tree2 = tree1.replicate('peer2');
+    root.newNamedChild('a');
+    tree2.setVertexProperty(root.id, 'title', 'Hello');
+
+    const encoded = new StateVector(tree2.getStateVector()!).encode();
+    expect(tree1.getMissingOps(encoded)).toEqual(tree1.getMissingOps(tree2.getStateVector()!));
+    expect(tree1.getMissingOps(encoded).length).toBe(3);
+  });
+});
@@ -0,0 +1,56 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Two-way sync', () => {
+  test('two diverged trees converge after one sync', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const docs = root.newNamedChild('Docs');
+    const tree2 = tree1.replicate('peer2');
+    tree1.popLocalOps();
+
+    // peer1: 3 ops for the new vertex (move, _c, name) and 1 property
+    const images = root.newNamedChild('Images');
+    docs.setProperty('color', 'blue');
+
+    // peer2: 3 ops for the new vertex, 1 move and 2 properties
+    const archive = tree2.newNamedVertex(root.id, 'Archive');
+    tree2.moveVertex(docs.id, archive.id);
+    tree2.setVertexProperty(root.id, 'title', 'Project');
+    tree2.setVertexProperty(docs.id, 'color', 'green');
+
+    expect(tree1.syncWith(tree2)).toEqual({ sent: 4, received: 6 });
+
+    expect(tree1.compareStructure(tree2)).toBe(true);
+    expect(tree1.getStateVector()).toEqual(tree2.getStateVector());
+    expect(tree1.getVertexProperty(root.id, 'title')).toBe('Project');
+    expect(tree1.getChildrenIds(archive.id)).toEqual([docs.id]);
+    expect(tree2.getVertex(images.id)?.name).toBe('Images');
+    expect(tree2.getVertexProperty(docs.id, 'color')).toBe(tree1.getVertexProperty(docs.id, 'color'));
+
+    // A second sync transfers nothing
+    expect(tree1.syncWith(tree2)).toEqual({ sent: 0, received: 0 });
+  });
+
+  test('edits on a fork stay separate until synced back', () => {
+    const original = new RepTree('peer1');
+    const root = original.createRoot();
+    const doc = root.newNamedChild('doc', { text: 'v1' });
+
+    const fork = original.replicate('fork');
+    fork.setVertexProperty(doc.id, 'text', 'v2 (what-if)');
+    const forkNote = fork.newNamedVertex(root.id, 'note');
+    original.setVertexProperty(root.id, 'title', 'Original');
+
+    expect(original.getVertexProperty(doc.id, 'text')).toBe('v1');
+    expect(original.getVertex(forkNote.id)).toBeUndefined();
+    expect(fork.getVertexProperty(root.id, 'title')).toBeUndefined();
+
+    original.syncWith(fork);
+
+    expect(original.compareStructure(fork)).toBe(true);
+    expect(original.getVertexProperty(doc.id, 'text')).toBe('v2 (what-if)');
+    expect(original.getVertex(forkNote.id)?.name).toBe('note');
+    expect(fork.getVertexProperty(root.id, 'title')).toBe('Original');
+  });
+});
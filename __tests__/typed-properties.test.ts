@@ -0,0 +1,56 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree } from '../src';
+
+describe('Typed property accessors', () => {
+  function createVertex() {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const v = root.newChild({
+      title: 'Task',
+      progress: 0.5,
+      count: 3,
+      done: false,
+      nothing: null,
+      tags: ['a'],
+    });
+    return { tree, v };
+  }
+
+  test('return values of the requested type', () => {
+    const { tree, v } = createVertex();
+
+    expect(tree.getStringProperty(v.id, 'title')).toBe('Task');
+    expect(tree.getNumberProperty(v.id, 'progress')).toBe(0.5);
+    expect(tree.getNumberProperty(v.id, 'count')).toBe(3);
+    expect(tree.getIntegerProperty(v.id, 'count')).toBe(3);
+    expect(tree.getBooleanProperty(v.id, 'done')).toBe(false);
+  });
+
+  test('return undefined for missing keys and vertices', () => {
+    const { tree, v } = createVertex();
+
+    expect(tree.getStringProperty(v.id, 'missing')).toBeUndefined();
+    expect(tree.getNumberProperty(v.id, 'missing')).toBeUndefined();
+    expect(tree.getIntegerProperty(v.id, 'missing')).toBeUndefined();
+    expect(tree.getBooleanProperty(v.id, 'missing')).toBeUndefined();
+    expect(tree.getStringProperty('missing', 'title')).toBeUndefined();
+  });
+
+  test('throw on type mismatch', () => {
+    const { tree, v } = createVertex();
+
+    expect(() => tree.getStringProperty(v.id, 'count')).toThrowError(/not a string/);
+    expect(() => tree.getNumberProperty(v.id, 'title')).toThrowError(/not a number/);
+    expect(() => tree.getIntegerProperty(v.id, 'progress')).toThrowError(/not an integer/);
+    expect(() => tree.getBooleanProperty(v.id, 'tags')).toThrowError(/not a boolean/);
+    expect(() => tree.getStringProperty(v.id, 'nothing')).toThrowError(/not a string/);
+  });
+
+  test('respect transient overlays', () => {
+    const { tree, v } = createVertex();
+    v.setTransientProperty('title', 'Draft');
+
+    expect(tree.getStringProperty(v.id, 'title')).toBe('Draft');
+    expect(tree.getStringProperty(v.id, 'title', false)).toBe('Task');
+  });
+});
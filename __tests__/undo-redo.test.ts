@@ -0,0 +1,121 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, isMoveVertexOp, isAnyPropertyOp } from '../src';
+
+describe('Undo and redo', () => {
+  test('undoes and redoes a move and a property set with new ops', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = root.newNamedChild('b');
+    const note = a.newNamedChild('note');
+
+    note.setProperty('text', 'Hello');
+    note.setProperty('text', 'Hello, world');
+    tree.moveVertex(note.id, b.id);
+    tree.popLocalOps();
+
+    expect(tree.undo()).toBe(true);
+    expect(note.parentId).toBe(a.id);
+
+    expect(tree.undo()).toBe(true);
+    expect(note.getProperty('text')).toBe('Hello');
+
+    // Undo emits regular forward ops
+    const undoOps = tree.popLocalOps();
+    expect(undoOps.length).toBe(2);
+    expect(isMoveVertexOp(undoOps[0])).toBe(true);
+    expect(isAnyPropertyOp(undoOps[1])).toBe(true);
+
+    expect(tree.redo()).toBe(true);
+    expect(note.getProperty('text')).toBe('Hello, world');
+
+    expect(tree.redo()).toBe(true);
+    expect(note.parentId).toBe(b.id);
+    expect(tree.canRedo).toBe(false);
+    expect(tree.popLocalOps().length).toBe(2);
+
+    const replica = new RepTree('peer2', tree.getAllOps());
+    expect(replica.compareStructure(tree)).toBe(true);
+    expect(replica.getVertexProperty(note.id, 'text')).toBe('Hello, world');
+  });
+
+  test('undoing a vertex creation moves it out of the tree', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const child = root.newNamedChild('child', { color: 'red' });
+
+    expect(tree.undo()).toBe(true);
+    expect(root.childrenIds).toEqual([]);
+    expect(tree.canUndo).toBe(false);
+
+    expect(tree.redo()).toBe(true);
+    expect(root.childrenIds).toEqual([child.id]);
+    expect(child.getProperty('color')).toBe('red');
+    expect(child.name).toBe('child');
+  });
+
+  test('a new edit clears the redo stack', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    root.setProperty('title', 'One');
+    root.setProperty('title', 'Two');
+
+    tree.undo();
+    expect(tree.canRedo).toBe(true);
+
+    root.setProperty('title', 'Three');
+    expect(tree.canRedo).toBe(false);
+    expect(tree.redo()).toBe(false);
+  });
+
+  test('edits merged from other peers are not undone', () => {
+    const tree1 = new RepTree('peer1');
+    const root = tree1.createRoot();
+    const tree2 = tree1.replicate('peer2');
+
+    tree2.setVertexProperty(root.id, 'title', 'Remote');
+    tree1.merge(tree2.getAllOps());
+
+    expect(tree1.canUndo).toBe(false);
+    expect(tree1.undo()).toBe(false);
+    expect(tree1.getVertexProperty(root.id, 'title')).toBe('Remote');
+  });
+
+  test('keeps at most maxUndoSteps edits', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    tree.maxUndoSteps = 3;
+
+    for (let i = 1; i <= 10; i++) {
+      root.setProperty('count', i);
+    }
+
+    expect(tree.canUndo).toBe(true);
+    expect(tree.undo()).toBe(true);
+    expect(tree.undo()).toBe(true);
+    expect(tree.undo()).toBe(true);
+    // Older edits were dropped from the history
+    expect(tree.canUndo).toBe(false);
+    expect(tree.undo()).toBe(false);
+    expect(root.getProperty('count')).toBe(7);
+
+    expect(tree.canRedo).toBe(true);
+    expect(tree.redo()).toBe(true);
+    expect(tree.redo()).toBe(true);
+    expect(tree.redo()).toBe(true);
+    expect(tree.canRedo).toBe(false);
+    expect(root.getProperty('count')).toBe(10);
+  });
+
+  test('tracks nothing when maxUndoSteps is 0', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    tree.maxUndoSteps = 0;
+
+    root.newNamedChild('child');
+    root.setProperty('title', 'Hello');
+
+    expect(tree.canUndo).toBe(false);
+    expect(tree.undo()).toBe(false);
+  });
+});
@@ -37,6 +37,25 @@ describe('Unsupported property value types are rejected', () => {
     expect(v.getProperty('ok')).toEqual({ n: 1, arr: [1, { x: true }] });
   });
 
+  test('non-finite numbers are rejected', () => {
+    const t = new RepTree('p');
+    const root = t.createRoot();
+    const v = root.newChild();
+
+    for (const value of [NaN, Infinity, -Infinity]) {
+      expect(() => v.setProperty('n', value)).toThrowError(/Unsupported property value/);
+      expect(() => v.setProperty('nested', { list: [1, value] } as any)).toThrowError(/Unsupported property value/);
+      expect(() => v.setTransientProperty('n', value)).toThrowError(/Unsupported transient property value/);
+    }
+
+    expect(v.getProperty('n')).toBeUndefined();
+    expect(v.getProperty('nested')).toBeUndefined();
+
+    // Regular numbers are fine
+    v.setProperty('n', Number.MAX_VALUE);
+    expect(v.getProperty('n')).toBe(Number.MAX_VALUE);
+  });
+
   test('newChild/newNamedChild reject invalid values in props normalization', () => {
     const t = new RepTree('p');
     const root = t.createRoot();
@@ -0,0 +1,228 @@
+import { RepTree, opIdToString, type VertexOperation } from '../../src';
+import deepEqual from '../../src/utils/deepEqual';
+
+/**
+ * Result of a failed convergence check
+ */
+export type ConvergenceFailure = {
+  /** What differs between the replicas */
+  reason: string;
+  /** The smallest subset of ops we found that still makes the replicas diverge */
+  ops: VertexOperation[];
+};
+
+/**
+ * Creates a seeded pseudo-random generator (mulberry32), so a failing run can be reproduced from its seed
+ *
+ * @param seed The seed of the generator
+ * @returns A function returning numbers in [0, 1)
+ */
+export function createRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6D2B79F5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+/**
+ * Returns a shuffled copy of the items (Fisher-Yates)
+ */
+export function shuffle<T>(items: ReadonlyArray<T>, random: () => number): T[] {
+  const result = [...items];
+  for (let i = result.length - 1; i > 0; i--) {
+    const j = Math.floor(random() * (i + 1));
+    [result[i], result[j]] = [result[j], result[i]];
+  }
+  return result;
+}
+
+/**
+ * Generates a causally valid set of concurrent ops.
+ *
+ * Several peers edit their own replicas of the same tree and every now and then
+ * pull ops from each other. Because every op is produced by a real tree, parents are
+ * always created before their children and ops only reference vertices their peer has seen.
+ *
+ * @param random The random generator to use
+ * @param peersCount Number of peers editing the tree
+ * @param actionsPerPeer Number of random actions each peer performs
+ * @returns All unique ops produced by the peers
+ */
+export function generateConcurrentOps(random: () => number, peersCount: number, actionsPerPeer: number): VertexOperation[] {
+  const origin = new RepTree('origin');
+  origin.createRoot();
+
+  const peers: RepTree[] = [];
+  for (let i = 0; i < peersCount; i++) {
+    peers.push(origin.replicate(`peer${i + 1}`));
+  }
+
+  for (let i = 0; i < actionsPerPeer; i++) {
+    for (const peer of peers) {
+      executeSeededRandomAction(peer, random);
+    }
+
+    if (random() < 0.1) {
+      const a = peers[Math.floor(random() * peers.length)];
+      const b = peers[Math.floor(random() * peers.length)];
+      a.merge(b.getMissingOps(a.getStateVector() ?? {}));
+    }
+  }
+
+  const uniqueOps = new Map<string, VertexOperation>();
+  for (const tree of [origin, ...peers]) {
+    for (const op of tree.getAllOps()) {
+      uniqueOps.set(opIdToString(op.id), op);
+    }
+  }
+
+  return [...uniqueOps.values()];
+}
+
+/**
+ * Creates, moves, deletes a vertex or sets a property using the provided random generator
+ */
+function executeSeededRandomAction(tree: RepTree, random: () => number): void {
+  const pick = <T>(items: T[]): T => items[Math.floor(random() * items.length)];
+
+  const rootId = tree.root!.id;
+  // Skip the null vertex that holds deleted vertices
+  const vertices = tree.getAllVertices().filter(v => v.id !== '0');
+  const movableVertices = vertices.filter(v => v.id !== rootId);
+
+  const roll = random();
+  if (roll < 0.3 || movableVertices.length < 2) {
+    tree.newVertex(pick(vertices).id);
+  } else if (roll < 0.6) {
+    tree.moveVertex(pick(movableVertices).id, pick(vertices).id);
+  } else if (roll < 0.9) {
+    tree.setVertexProperty(pick(vertices).id, `prop_${Math.floor(random() * 3)}`, Math.floor(random() * 10));
+  } else {
+    tree.deleteVertex(pick(movableVertices).id);
+  }
+}
+
+/**
+ * Compares every vertex (including deleted ones) and the state vectors of two trees
+ *
+ * @returns A description of the first difference or null if the trees are identical
+ */
+export function findDivergence(a: RepTree, b: RepTree): string | null {
+  if (!a.compareStructure(b)) {
+    return 'The structures of the trees differ';
+  }
+
+  const verticesA = a.getAllVertices();
+  const verticesB = new Map(b.getAllVertices().map(v => [v.id, v]));
+
+  if (verticesA.length !== verticesB.size) {
+    return `Vertex counts differ: ${verticesA.length} vs ${verticesB.size}`;
+  }
+
+  for (const vertexA of verticesA) {
+    const vertexB = verticesB.get(vertexA.id);
+    if (!vertexB) {
+      return `Vertex ${vertexA.id} is missing in the second tree`;
+    }
+
+    if (vertexA.parentId !== vertexB.parentId) {
+      return `Vertex ${vertexA.id} has different parents: ${vertexA.parentId} vs ${vertexB.parentId}`;
+    }
+
+    if (!deepEqual(vertexA.getProperties(), vertexB.getProperties())) {
+      return `Vertex ${vertexA.id} has different properties`;
+    }
+  }
+
+  if (!deepEqual(a.getStateVector(), b.getStateVector())) {
+    return 'State vectors differ';
+  }
+
+  return null;
+}
+
+/**
+ * Applies the same ops in several random orders to fresh trees and checks that all of them converge
+ *
+ * @returns A description of the first difference or null if all trees converged
+ */
+export function findDivergenceBetweenOrders(ops: ReadonlyArray<VertexOperation>, seed: number, ordersCount: number): string | null {
+  const random = createRandom(seed);
+  const reference = new RepTree('observer', shuffle(ops, random));
+
+  for (let i = 1; i < ordersCount; i++) {
+    const other = new RepTree('observer', shuffle(ops, random));
+    const divergence = findDivergence(reference, other);
+    if (divergence) {
+      return divergence;
+    }
+  }
+
+  return null;
+}
+
+/**
+ * Removes chunks of ops as long as the remaining ops still fail the check
+ *
+ * @param ops The failing ops
+ * @param fails Returns true if the given ops still reproduce the failure
+ * @returns A smaller (not necessarily minimal) set of failing ops
+ */
+export function shrinkOps(ops: ReadonlyArray<VertexOperation>, fails: (ops: VertexOperation[]) => boolean): VertexOperation[] {
+  let current = [...ops];
+  let chunkSize = Math.ceil(current.length / 2);
+
+  while (chunkSize >= 1) {
+    let removedAny = false;
+
+    for (let start = 0; start < current.length; start += chunkSize) {
+      const candidate = [...current.slice(0, start), ...current.slice(start + chunkSize)];
+      if (candidate.length > 0 && fails(candidate)) {
+        current = candidate;
+        removedAny = true;
+        // Retry at the same position since the next chunk has shifted into it
+        start -= chunkSize;
+      }
+    }
+
+    if (!removedAny) {
+      chunkSize = Math.floor(chunkSize / 2);
+    }
+  }
+
+  return current;
+}
+
+/**
+ * Checks that applying the ops in different random orders always produces identical trees.
+ * If they don't, the ops are shrunk to a smaller set that still reproduces the divergence.
+ *
+ * @param ops Causally valid ops, e.g. from `generateConcurrentOps`
+ * @param seed The seed for the random orders
+ * @param ordersCount How many different orders to try
+ * @returns The failure with the shrunk ops or null if the trees converged
+ */
+export function checkConvergence(ops: ReadonlyArray<VertexOperation>, seed: number, ordersCount: number = 3): ConvergenceFailure | null {
+  const reason = findDivergenceBetweenOrders(ops, seed, ordersCount);
+  if (!reason) {
+    return null;
+  }
+
+  const shrunkOps = shrinkOps(ops, candidate => {
+    try {
+      return findDivergenceBetweenOrders(candidate, seed, ordersCount) !== null;
+    } catch {
+      // A subset without the root vertex can't form a tree
+      return false;
+    }
+  });
+
+  return {
+    reason: findDivergenceBetweenOrders(shrunkOps, seed, ordersCount) ?? reason,
+    ops: shrunkOps,
+  };
+}
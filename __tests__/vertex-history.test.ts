@@ -0,0 +1,41 @@
+import { describe, test, expect } from 'vitest';
+import { RepTree, isMoveVertexOp, isAnyPropertyOp, compareOpId } from '../src';
+
+describe('Vertex history', () => {
+  test('lists every op that targeted the vertex in causal order', () => {
+    const tree = new RepTree('peer1');
+    const root = tree.createRoot();
+    const a = root.newNamedChild('a');
+    const b = root.newNamedChild('b');
+
+    const vertex = tree.newVertex(root.id);
+    tree.moveVertex(vertex.id, a.id);
+    vertex.setProperty('status', 'draft');
+    tree.moveVertex(vertex.id, b.id);
+    vertex.setProperty('status', 'review');
+    vertex.setProperty('status', 'done');
+
+    // Edits on other vertices are not included
+    a.setProperty('status', 'done');
+
+    const history = tree.getVertexHistory(vertex.id);
+
+    // The creation move and its `_c` come first, then the 5 edits
+    expect(history.length).toBe(7);
+    expect(history.every(op => op.targetId === vertex.id)).toBe(true);
+    for (let i = 1; i < history.length; i++) {
+      expect(compareOpId(history[i - 1].id, history[i].id)).toBe(-1);
+    }
+
+    const edits = history.slice(2).map(op => {
+      if (isMoveVertexOp(op)) return `move:${op.parentId}`;
+      if (isAnyPropertyOp(op)) return `${op.key}:${op.value}`;
+      return '';
+    });
+    expect(edits).toEqual([`move:${a.id}`, 'status:draft', `move:${b.id}`, 'status:review', 'status:done']);
+
+    // A replica built from shuffled ops reports the same history
+    const replica = new RepTree('peer2', [...tree.getAllOps()].reverse());
+    expect(replica.getVertexHistory(vertex.id)).toEqual(history);
+  });
+});
@@ -8,7 +8,7 @@ import {
   newSetTransientVertexPropertyOp,
   isAnyPropertyOp
 } from "./operations";
-import type { VertexPropertyType, TreeVertexProperty, VertexChangeEvent, TreeVertexId, VertexMoveEvent } from "./treeTypes";
+import type { VertexPropertyType, TreeVertexProperty, VertexChangeEvent, TreeVertexId, VertexMoveEvent, OpIdRange, VertexJson, JsonValue, IntegrityReport } from "./treeTypes";
 import { VertexState } from "./VertexState";
 import { TreeState } from "./TreeState";
 import { type OpId, compareOpId, equalsOpId, isOpIdGreaterThan, opIdToString } from "./OpId";
@@ -20,6 +20,14 @@ import isJsonValue from './utils/isJsonValue';
 
 type PropertyKeyAtVertexId = `${string}@${TreeVertexId}`;
 
+/**
+ * The state captured before a local edit, enough to emit an op that reverts it.
+ * `prevParentId` is undefined when the move created the vertex.
+ */
+type UndoStep =
+  | { type: 'move'; vertexId: string; prevParentId: string | null | undefined }
+  | { type: 'property'; vertexId: string; key: string; prevValue: VertexPropertyType | undefined };
+
 /**
  * RepTree is a tree data structure for storing vertices with properties.
  * It uses 2 conflict-free replicated data types (CRDTs) to manage seamless replication between peers.
@@ -30,6 +38,10 @@ export class RepTree {
   private static NULL_VERTEX_ID = '0';
 
   readonly peerId: string;
+  /** Generates IDs for new vertices. UUIDv4 without dashes by default, replace it for deterministic or custom IDs. */
+  idGenerator: () => string = uuid;
+  /** How many local edits `undo` can revert, older ones are dropped. Set to 0 to turn undo tracking off. */
+  maxUndoSteps = 100;
   private rootVertexId: string | undefined;
 
   private lamportClock = 0;
@@ -41,21 +53,39 @@ export class RepTree {
   private localOps: VertexOperation[] = [];
   private pendingMovesWithMissingParent: Map<string, MoveVertex[]> = new Map();
   private pendingPropertiesWithMissingVertex: Map<string, SetVertexProperty[]> = new Map();
+  private pendingOpsWithMissingDeps: Map<string, VertexOperation[]> = new Map();
+  // OpIds (as strings) of applied ops that ops in `pendingOpsWithMissingDeps` wait for
+  private satisfiedDepIds: string[] = [];
+  // OpIds (as strings) of all ops in the pending buffers above
+  private pendingOpIds: Set<string> = new Set();
   private knownOps: Set<string> = new Set();
   private parentIdBeforeMove: Map<OpId, string | null | undefined> = new Map();
   private opAppliedCallbacks: ((op: VertexOperation) => void)[] = [];
+  private moveGuards: ((vertexId: string, parentId: string) => boolean)[] = [];
+  private undoStack: UndoStep[][] = [];
+  private redoStack: UndoStep[][] = [];
+  private undoGroup: UndoStep[] | null = null;
 
   // State vector tracking operations from each peer
   private stateVector: StateVector;
   private _stateVectorEnabled: boolean = true;
 
   /**
-   * @param peerId - The peer ID of the current client. Should be unique across all peers.
+   * @param peerId - The peer ID of the current client. Should be unique across all peers. Can't be empty or contain '@'.
    * @param ops - The operations to replicate an existing tree, if not provided - an empty tree will be created without a root vertex
    */
   constructor(peerId: string, ops?: ReadonlyArray<VertexOperation>) {
+    if (!peerId) {
+      throw new Error("Peer ID can't be empty");
+    }
+    // '@' separates the counter from the peer ID in OpId strings
+    if (peerId.includes('@')) {
+      throw new Error(`Peer ID can't contain '@': ${peerId}`);
+    }
+
     this.peerId = peerId;
-    this.state = new TreeState();
+    // Children created in the same millisecond are ordered by the ops that set their creation date
+    this.state = new TreeState((a, b) => this.compareCreationOpIds(a.id, b.id));
 
     // Initialize state vector (enabled by default)
     this.stateVector = new StateVector();
@@ -98,6 +128,10 @@ export class RepTree {
     return new Vertex(this, rootVertex);
   }
 
+  /**
+   * Creates an independent copy of the tree with its own peer ID, e.g. a fork for speculative edits.
+   * Edits on either tree don't affect the other until they're synced (see `syncWith`).
+   */
   replicate(newPeerId: string): RepTree {
     return new RepTree(newPeerId, this.getAllOps());
   }
@@ -110,6 +144,72 @@ export class RepTree {
     return [...this.moveOps, ...this.setPropertyOps];
   }
 
+  /**
+   * Scans the whole tree state for cycles and vertices with a missing parent.
+   * The CRDT never produces them, so anything reported there points to a bug or a corrupted state.
+   * Also lists vertices that pending ops reference but that don't exist yet.
+   */
+  checkIntegrity(): IntegrityReport {
+    const missingVertices = new Set([...this.pendingMovesWithMissingParent.keys(), ...this.pendingPropertiesWithMissingVertex.keys()]);
+    const report: IntegrityReport = { cycles: [], orphans: [], missingVertices: [...missingVertices].sort() };
+    // Vertices we already know lead to a root, an orphan or a reported cycle
+    const checked = new Set<string>();
+
+    for (const vertex of this.state.getAllVertices()) {
+      if (vertex.parentId !== null && !this.state.getVertex(vertex.parentId)) {
+        report.orphans.push(vertex.id);
+      }
+
+      const path: string[] = [];
+      const pathIndex = new Map<string, number>();
+      let current: VertexState | undefined = vertex;
+      while (current && !checked.has(current.id)) {
+        if (pathIndex.has(current.id)) {
+          report.cycles.push(path.slice(pathIndex.get(current.id)));
+          break;
+        }
+        pathIndex.set(current.id, path.length);
+        path.push(current.id);
+        current = current.parentId !== null ? this.state.getVertex(current.parentId) : undefined;
+      }
+
+      for (const id of path) checked.add(id);
+    }
+
+    return report;
+  }
+
+  /**
+   * Checks whether the op was already applied (or lost to a newer property op), so a received op can be skipped cheaply.
+   * Ops waiting in the pending buffers don't count as applied yet.
+   */
+  hasOp(opId: OpId): boolean {
+    return this.knownOps.has(opIdToString(opId));
+  }
+
+  /**
+   * Returns the sorted IDs of all peers that contributed operations to the tree, including this peer if it made any.
+   * Ops still waiting in the pending buffers are not counted.
+   */
+  getKnownPeerIds(): string[] {
+    const peerIds = new Set<string>();
+    for (const op of this.moveOps) peerIds.add(op.id.peerId);
+    for (const op of this.setPropertyOps) peerIds.add(op.id.peerId);
+    return [...peerIds].sort();
+  }
+
+  /**
+   * Returns every move and property op that targeted the vertex (including the one that created it), sorted by OpId.
+   * Useful for auditing and debugging how a vertex got to its current state.
+   */
+  getVertexHistory(vertexId: string): VertexOperation[] {
+    const ops: VertexOperation[] = [
+      ...this.moveOps.filter(op => op.targetId === vertexId),
+      ...this.setPropertyOps.filter(op => op.targetId === vertexId),
+    ];
+    return ops.sort((a, b) => compareOpId(a.id, b.id));
+  }
+
   getVertex(vertexId: string): Vertex | undefined {
     const vertex = this.state.getVertex(vertexId);
     return vertex ? new Vertex(this, vertex) : undefined;
@@ -133,22 +233,68 @@ export class RepTree {
     return this.state.getChildrenIds(vertexId);
   }
 
-  /** Returns the ancestors of the given vertex. The first element is the root vertex. */
+  /**
+   * Returns the ancestors of the given vertex, starting from its parent and ending with the root vertex.
+   * Doesn't include the vertex itself.
+   */
   getAncestors(vertexId: string): Vertex[] {
-    const ancestors: Vertex[] = [];
+    return this.getAncestorIds(vertexId).map(id => new Vertex(this, this.state.getVertex(id)!));
+  }
+
+  /**
+   * Returns the IDs of the ancestors of the given vertex, ordered the same way as in `getAncestors`.
+   * Throws if a cycle is detected on the way up to the root.
+   */
+  getAncestorIds(vertexId: string): string[] {
+    const ancestorIds: string[] = [];
+    const visitedVertices = new Set<string>([vertexId]);
     let currentVertex = this.state.getVertex(vertexId);
 
     while (currentVertex && currentVertex.parentId) {
       const parentVertex = this.state.getVertex(currentVertex.parentId);
-      if (parentVertex) {
-        ancestors.push(new Vertex(this, parentVertex));
-        currentVertex = parentVertex;
-      } else {
+      if (!parentVertex) {
         break;
       }
+
+      if (visitedVertices.has(parentVertex.id)) {
+        throw new Error(`Cycle detected in the ancestors of vertex ${vertexId}`);
+      }
+
+      visitedVertices.add(parentVertex.id);
+      ancestorIds.push(parentVertex.id);
+      currentVertex = parentVertex;
     }
 
-    return ancestors;
+    return ancestorIds;
+  }
+
+  /**
+   * Returns all descendants of the vertex in breadth-first order, each with its depth below the vertex:
+   * 0 for direct children, 1 for grandchildren and so on.
+   * @param maxDepth - The deepest level to include, e.g. 0 returns only direct children. Unlimited if not set.
+   */
+  getDescendants(vertexId: string, maxDepth?: number): { vertex: Vertex, depth: number }[] {
+    const descendants: { vertex: Vertex, depth: number }[] = [];
+    // Set to track visited vertices and protect from cycles
+    const visitedVertices = new Set<string>([vertexId]);
+    let level = this.state.getChildren(vertexId);
+    let depth = 0;
+
+    while (level.length > 0 && (maxDepth === undefined || depth <= maxDepth)) {
+      const nextLevel: VertexState[] = [];
+      for (const vertex of level) {
+        if (visitedVertices.has(vertex.id)) {
+          continue;
+        }
+        visitedVertices.add(vertex.id);
+        descendants.push({ vertex: new Vertex(this, vertex), depth });
+        nextLevel.push(...this.state.getChildren(vertex.id));
+      }
+      level = nextLevel;
+      depth++;
+    }
+
+    return descendants;
   }
 
   getVertexProperty(vertexId: string, key: string, includingTransient: boolean = true): VertexPropertyType | undefined {
@@ -160,6 +306,45 @@ export class RepTree {
     return vertex.getProperty(key, includingTransient);
   }
 
+  /** Returns a string property or undefined if it's not set. Throws if the property has a different type. */
+  getStringProperty(vertexId: string, key: string, includingTransient: boolean = true): string | undefined {
+    return this.getTypedProperty<string>(vertexId, key, includingTransient, 'a string', v => typeof v === 'string');
+  }
+
+  /** Returns a number property or undefined if it's not set. Throws if the property has a different type. */
+  getNumberProperty(vertexId: string, key: string, includingTransient: boolean = true): number | undefined {
+    return this.getTypedProperty<number>(vertexId, key, includingTransient, 'a number', v => typeof v === 'number');
+  }
+
+  /** Returns an integer property or undefined if it's not set. Throws if the property is not an integer number. */
+  getIntegerProperty(vertexId: string, key: string, includingTransient: boolean = true): number | undefined {
+    return this.getTypedProperty<number>(vertexId, key, includingTransient, 'an integer', v => Number.isInteger(v));
+  }
+
+  /** Returns a boolean property or undefined if it's not set. Throws if the property has a different type. */
+  getBooleanProperty(vertexId: string, key: string, includingTransient: boolean = true): boolean | undefined {
+    return this.getTypedProperty<boolean>(vertexId, key, includingTransient, 'a boolean', v => typeof v === 'boolean');
+  }
+
+  private getTypedProperty<T extends VertexPropertyType>(
+    vertexId: string,
+    key: string,
+    includingTransient: boolean,
+    typeName: string,
+    isOfType: (value: VertexPropertyType) => boolean
+  ): T | undefined {
+    const value = this.getVertexProperty(vertexId, key, includingTransient);
+    if (value === undefined) {
+      return undefined;
+    }
+
+    if (!isOfType(value)) {
+      throw new Error(`Property "${key}" of vertex ${vertexId} is not ${typeName}: ${JSON.stringify(value)}`);
+    }
+
+    return value as T;
+  }
+
   getVertexProperties(vertexId: string): Readonly<TreeVertexProperty[]> {
     const vertex = this.state.getVertex(vertexId);
     if (!vertex) {
@@ -169,6 +354,18 @@ export class RepTree {
     return vertex.getAllProperties();
   }
 
+  /**
+   * Returns the number of received operations that wait to be applied,
+   * e.g. moves with a missing parent or properties of a vertex that doesn't exist yet.
+   */
+  getPendingOpsCount(): number {
+    let count = 0;
+    for (const ops of this.pendingMovesWithMissingParent.values()) count += ops.length;
+    for (const ops of this.pendingPropertiesWithMissingVertex.values()) count += ops.length;
+    for (const ops of this.pendingOpsWithMissingDeps.values()) count += ops.length;
+    return count;
+  }
+
   /**
    * Returns all local operations and clears the local operations list.
    * Can be used to get all operations that were generated from this peer and need to be sent to other peers.
@@ -190,7 +387,7 @@ export class RepTree {
       throw new Error('Root vertex already exists');
     }
 
-    this.rootVertexId = this.newVertexInternalWithUUID(null);
+    this.rootVertexId = this.newVertexInternalWithGeneratedId(null);
 
     const rootVertex = this.state.getVertex(this.rootVertexId);
     if (!rootVertex) {
@@ -201,12 +398,45 @@ export class RepTree {
   }
 
   newVertex(parentId: string, props: Record<string, VertexPropertyType> | object | null = null): Vertex {
-    const typedProps = props as Record<string, VertexPropertyType> | null;
-    const vertexId = this.newVertexInternalWithUUID(parentId);
-    if (typedProps) {
-      this.setVertexProperties(vertexId, typedProps);
+    return this.newVertexWithId(this.idGenerator(), parentId, props);
+  }
+
+  /**
+   * Returns the first child of the parent whose property equals the value, or creates a child with that property.
+   * If two peers create such a child concurrently, both children stay after a sync and every replica returns the
+   * same one: the first in the children order.
+   */
+  getOrCreateChild(parentId: string, key: string, value: VertexPropertyType): Vertex {
+    if (!this.state.getVertex(parentId)) {
+      throw new Error(`Parent vertex ${parentId} not found`);
+    }
+
+    const existing = this.getChildren(parentId).find(child => deepEqual(child.getProperty(key, false), value));
+    if (existing) {
+      return existing;
     }
 
+    return this.newVertex(parentId, { [key]: value });
+  }
+
+  /**
+   * Creates a vertex with an ID supplied by the caller instead of the ID generator.
+   * Throws if a vertex with this ID already exists.
+   */
+  newVertexWithId(vertexId: string, parentId: string, props: Record<string, VertexPropertyType> | object | null = null): Vertex {
+    if (this.state.getVertex(vertexId)) {
+      throw new Error(`Vertex ${vertexId} already exists`);
+    }
+    this.checkMoveGuards(vertexId, parentId);
+
+    const typedProps = props as Record<string, VertexPropertyType> | null;
+    this.withUndoGroup(() => {
+      this.newVertexInternal(vertexId, parentId);
+      if (typedProps) {
+        this.setVertexProperties(vertexId, typedProps);
+      }
+    });
+
     const vertex = this.state.getVertex(vertexId);
     if (!vertex) {
       throw new Error('Failed to create vertex');
@@ -216,11 +446,14 @@ export class RepTree {
 
   newNamedVertex(parentId: string, name: string, props: Record<string, VertexPropertyType> | object | null = null): Vertex {
     const typedProps = props as Record<string, VertexPropertyType> | null;
-    const vertexId = this.newVertexInternalWithUUID(parentId);
-    if (typedProps) {
-      this.setVertexProperties(vertexId, typedProps);
-    }
-    this.setVertexProperty(vertexId, 'name', name);
+    const vertexId = this.withUndoGroup(() => {
+      const vertexId = this.newVertexInternalWithGeneratedId(parentId);
+      if (typedProps) {
+        this.setVertexProperties(vertexId, typedProps);
+      }
+      this.setVertexProperty(vertexId, 'name', name);
+      return vertexId;
+    });
 
     const vertex = this.state.getVertex(vertexId);
     if (!vertex) {
@@ -230,12 +463,35 @@ export class RepTree {
   }
 
   moveVertex(vertexId: string, parentId: string) {
-    this.lamportClock++;
-    const op = newMoveVertexOp(this.lamportClock, this.peerId, vertexId, parentId);
-    this.localOps.push(op);
-    this.applyMove(op);
+    this.checkMoveGuards(vertexId, parentId);
+    this.withUndoGroup(() => this.moveVertexInternal(vertexId, parentId));
+  }
+
+  /**
+   * Moves the vertex with its whole subtree under a new parent. Descendants follow since they reference their parents.
+   * Unlike `moveVertex`, it checks the move locally and throws instead of emitting a move that would create a cycle
+   * (such a move is ignored by the move CRDT).
+   * @returns The ID of the move op
+   */
+  moveSubtree(vertexId: string, parentId: string): OpId {
+    if (!this.state.getVertex(vertexId)) {
+      throw new Error(`Vertex ${vertexId} not found`);
+    }
+    if (!this.state.getVertex(parentId)) {
+      throw new Error(`Parent vertex ${parentId} not found`);
+    }
+    if (vertexId === parentId || this.isAncestor(parentId, vertexId)) {
+      throw new Error(`Can't move vertex ${vertexId} under itself or its descendant ${parentId}`);
+    }
+
+    this.checkMoveGuards(vertexId, parentId);
+    return this.withUndoGroup(() => this.moveVertexInternal(vertexId, parentId));
   }
 
+  /**
+   * Deletes the vertex together with its whole subtree by moving it under the null vertex.
+   * It's a single move op, so the subtree disappears from the tree atomically on every peer.
+   */
   deleteVertex(vertexId: string) {
     this.moveVertex(vertexId, RepTree.NULL_VERTEX_ID);
   }
@@ -265,9 +521,11 @@ export class RepTree {
     const transientProps = vertex.getTransientProperties();
 
     // Promote each transient property to persistent
-    for (const prop of transientProps) {
-      this.setVertexProperty(vertexId, prop.key, prop.value);
-    }
+    this.withUndoGroup(() => {
+      for (const prop of transientProps) {
+        this.setVertexProperty(vertexId, prop.key, prop.value);
+      }
+    });
 
     // Clear transient OpIds tracking
     for (const prop of transientProps) {
@@ -280,42 +538,141 @@ export class RepTree {
 
   setVertexProperty(vertexId: string, key: string, value: VertexPropertyType) {
     // Runtime validation for JSON-serializable values (undefined is allowed for deletion)
-    const isJsonValue = (v: any): boolean => {
-      if (v === undefined) return true; // deletion signal
-      if (v === null) return true;
-      const t = typeof v;
-      if (t === 'string' || t === 'number' || t === 'boolean') return true;
-      if (t === 'bigint' || t === 'function' || t === 'symbol') return false;
-      if (Array.isArray(v)) return v.every(isJsonValue);
-      if (t === 'object') {
-        if (v instanceof Date) return false; // disallow Date objects
-        if (v instanceof Map || v instanceof Set || v instanceof RegExp) return false;
-        if (ArrayBuffer.isView(v)) return false; // TypedArrays
-        const proto = Object.getPrototypeOf(v);
-        if (proto !== Object.prototype && proto !== null) return false;
-        for (const val of Object.values(v)) {
-          if (!isJsonValue(val)) return false;
-        }
-        return true;
-      }
+    if (!isJsonValue(value)) {
+      throw new Error(`Unsupported property value for key "${key}"`);
+    }
+
+    this.withUndoGroup(() => this.setVertexPropertyInternal(vertexId, key, value));
+  }
+
+  /**
+   * Sets the property only if its current (persistent) value equals the expected one.
+   * It's a local check only: a concurrent edit from another peer can still win with last writer wins after a sync.
+   * @param expected - The value we expect the property to have, undefined if it shouldn't be set
+   * @returns True if the property was set
+   */
+  compareAndSetVertexProperty(vertexId: string, key: string, expected: VertexPropertyType, value: VertexPropertyType): boolean {
+    if (!this.state.getVertex(vertexId)) {
+      throw new Error(`Vertex ${vertexId} not found`);
+    }
+
+    if (!deepEqual(this.getVertexProperty(vertexId, key, false), expected)) {
       return false;
-    };
+    }
+
+    this.setVertexProperty(vertexId, key, value);
+    return true;
+  }
+
+  /**
+   * Removes the property from the vertex. It's a regular property op with an undefined value,
+   * so a concurrent set and unset resolve with last writer wins like any other property edits.
+   */
+  unsetVertexProperty(vertexId: string, key: string) {
+    this.setVertexProperty(vertexId, key, undefined);
+  }
 
+  /**
+   * Sets the same property on many vertices. Each vertex gets its own op, so concurrent edits resolve per vertex.
+   * @param throwOnMissing - If true, throws before setting anything when some of the vertices don't exist.
+   * Otherwise missing vertices are skipped.
+   * @returns The IDs of the vertices that don't exist and were skipped
+   */
+  setVertexPropertyForMany(vertexIds: ReadonlyArray<string>, key: string, value: VertexPropertyType, throwOnMissing: boolean = false): string[] {
     if (!isJsonValue(value)) {
       throw new Error(`Unsupported property value for key "${key}"`);
     }
 
-    this.lamportClock++;
-    const op = newSetVertexPropertyOp(this.lamportClock, this.peerId, vertexId, key, value as VertexPropertyType);
-    this.localOps.push(op);
-    this.applyProperty(op);
+    const missingVertexIds = vertexIds.filter(id => !this.state.getVertex(id));
+    if (throwOnMissing && missingVertexIds.length > 0) {
+      throw new Error(`Vertices not found: ${missingVertexIds.join(', ')}`);
+    }
+
+    this.withUndoGroup(() => {
+      for (const vertexId of vertexIds) {
+        if (this.state.getVertex(vertexId)) {
+          this.setVertexProperty(vertexId, key, value);
+        }
+      }
+    });
+
+    return missingVertexIds;
   }
 
   setVertexProperties(vertexId: string, props: Record<string, VertexPropertyType> | object) {
     const typedProps = props as Record<string, VertexPropertyType>;
-    for (const [key, value] of Object.entries(typedProps)) {
-      this.setVertexProperty(vertexId, key, value);
+    this.withUndoGroup(() => {
+      for (const [key, value] of Object.entries(typedProps)) {
+        this.setVertexProperty(vertexId, key, value);
+      }
+    });
+  }
+
+  get canUndo(): boolean {
+    return this.undoStack.length > 0;
+  }
+
+  get canRedo(): boolean {
+    return this.redoStack.length > 0;
+  }
+
+  /**
+   * Reverts the last local edit by emitting new ops that restore the state captured before it
+   * (a move back to the previous parent, a property set to the previous value).
+   * The ops are regular forward ops, so they replicate and resolve conflicts like any other edit.
+   * Ops merged from other peers and transient properties are not tracked.
+   * @returns False if there is nothing to undo
+   */
+  undo(): boolean {
+    const steps = this.undoStack[this.undoStack.length - 1];
+    if (!steps) {
+      return false;
     }
+
+    // Pop only after reverting, so the edit stays undoable if a move guard rejects it
+    const inverseSteps = this.revertUndoSteps(steps);
+    this.undoStack.pop();
+    this.redoStack.push(inverseSteps);
+    return true;
+  }
+
+  /**
+   * Re-applies the last undone edit, also as new forward ops.
+   * @returns False if there is nothing to redo
+   */
+  redo(): boolean {
+    const steps = this.redoStack[this.redoStack.length - 1];
+    if (!steps) {
+      return false;
+    }
+
+    const inverseSteps = this.revertUndoSteps(steps);
+    this.redoStack.pop();
+    this.pushUndoGroup(inverseSteps);
+    return true;
+  }
+
+  /**
+   * Finds the vertices in the tree (the root and its descendants, deleted vertices are skipped) that have the property.
+   * @param value - If provided, only vertices where the property equals the value are returned
+   * @param includingTransient - Whether to match transient values too; by default only persisted values are matched
+   */
+  findVerticesWithProperty(key: string, value?: VertexPropertyType, includingTransient: boolean = false): Vertex[] {
+    const root = this.root;
+    if (!root) {
+      return [];
+    }
+
+    const matches = (vertex: Vertex) => {
+      const propertyValue = vertex.getProperty(key, includingTransient);
+      if (propertyValue === undefined) {
+        return false;
+      }
+      return value === undefined || deepEqual(propertyValue, value);
+    };
+
+    const candidates = [root, ...this.getDescendants(root.id).map(d => d.vertex)];
+    return candidates.filter(matches);
   }
 
   getVertexByPath(path: string): Vertex | undefined {
@@ -355,6 +712,102 @@ export class RepTree {
     return undefined;
   }
 
+  /**
+   * Exports the vertex and its whole subtree as a nested JSON object.
+   * Children are ordered the same way as in `getChildren`. Transient properties are not included.
+   * @returns The JSON object or undefined if the vertex doesn't exist
+   */
+  exportJson(vertexId: string): VertexJson | undefined {
+    const vertex = this.state.getVertex(vertexId);
+    if (!vertex) {
+      return undefined;
+    }
+
+    const vertexToJson = (v: VertexState): VertexJson => {
+      const properties: Record<string, JsonValue> = {};
+      for (const prop of v.getAllProperties(false)) {
+        properties[prop.key] = prop.value as JsonValue;
+      }
+      return { id: v.id, properties, children: [] };
+    };
+
+    // We use a stack instead of recursion so deep trees don't overflow the call stack
+    const rootJson = vertexToJson(vertex);
+    const stack: [VertexState, VertexJson][] = [[vertex, rootJson]];
+    while (stack.length > 0) {
+      const [current, currentJson] = stack.pop()!;
+      for (const child of this.state.getChildren(current.id)) {
+        const childJson = vertexToJson(child);
+        currentJson.children.push(childJson);
+        stack.push([child, childJson]);
+      }
+    }
+
+    return rootJson;
+  }
+
+  /**
+   * Imports a nested JSON object (in the shape produced by `exportJson`) as a new subtree under the given parent.
+   * `properties` and `children` are optional, `id` fields are ignored and fresh IDs are generated.
+   * Throws if the JSON is malformed; nothing is imported in that case.
+   * @returns The root vertex of the imported subtree
+   */
+  importJson(parentId: string, json: unknown): Vertex {
+    if (!this.state.getVertex(parentId)) {
+      throw new Error(`Parent vertex ${parentId} not found`);
+    }
+
+    const isObject = (v: unknown): v is Record<string, unknown> =>
+      typeof v === 'object' && v !== null && !Array.isArray(v);
+
+    // Validate the whole JSON first so we don't leave a half-imported subtree behind
+    const toValidate: unknown[] = [json];
+    while (toValidate.length > 0) {
+      const node = toValidate.pop();
+      if (!isObject(node)) {
+        throw new Error('Invalid vertex JSON: expected an object');
+      }
+
+      const { properties, children } = node;
+
+      if (properties !== undefined) {
+        if (!isObject(properties)) {
+          throw new Error('Invalid vertex JSON: "properties" must be an object');
+        }
+        for (const [key, value] of Object.entries(properties)) {
+          if (value === undefined || !isJsonValue(value)) {
+            throw new Error(`Unsupported property value for key "${key}"`);
+          }
+        }
+      }
+
+      if (children !== undefined) {
+        if (!Array.isArray(children)) {
+          throw new Error('Invalid vertex JSON: "children" must be an array');
+        }
+        toValidate.push(...children);
+      }
+    }
+
+    type NodeJson = { properties?: Record<string, VertexPropertyType>; children?: NodeJson[] };
+
+    const rootJson = json as NodeJson;
+    return this.withUndoGroup(() => {
+      const root = this.newVertex(parentId, rootJson.properties ?? null);
+      const stack: [string, NodeJson][] = [[root.id, rootJson]];
+      while (stack.length > 0) {
+        const [vertexId, node] = stack.pop()!;
+        // Create siblings in order so they keep their order when there's no `_c` in the JSON
+        for (const childJson of node.children ?? []) {
+          const child = this.newVertex(vertexId, childJson.properties ?? null);
+          stack.push([child.id, childJson]);
+        }
+      }
+
+      return root;
+    });
+  }
+
   printTree() {
     if (!this.rootVertexId) {
       return '';
@@ -512,6 +965,17 @@ export class RepTree {
     return () => this.opAppliedCallbacks = this.opAppliedCallbacks.filter(l => l !== callback);
   }
 
+  /**
+   * Registers a guard that can reject local moves by returning false; the call that makes the move throws then.
+   * Guards run on `moveVertex`, `moveSubtree`, deletes, `undo`/`redo` and on creating vertices (`newVertex`,
+   * `newNamedVertex`, `newVertexWithId`, `getOrCreateChild`, `importJson`), since a creation is a move of a fresh vertex.
+   * Moves merged from other peers are not checked, so all replicas still converge.
+   */
+  addMoveGuard(guard: (vertexId: string, parentId: string) => boolean): () => void {
+    this.moveGuards.push(guard);
+    return () => this.moveGuards = this.moveGuards.filter(g => g !== guard);
+  }
+
   static compareVertices(vertexId: string, treeA: RepTree, treeB: RepTree): boolean {
     const childrenA = treeA.state.getChildrenIds(vertexId);
     const childrenB = treeB.state.getChildrenIds(vertexId);
@@ -551,21 +1015,121 @@ export class RepTree {
   }
 
   private newVertexInternal(vertexId: string, parentId: string | null): string {
-    this.lamportClock++;
     // To create a vertex - we move a vertex with a fresh id under the parent.
     // No need to have a separate "create vertex" operation.
+    this.moveVertexInternal(vertexId, parentId);
+
+    // Set the creation date. Undoing the creation moves the vertex away, so the date stays.
+    this.lamportClock++;
+    const op = newSetVertexPropertyOp(this.lamportClock, this.peerId, vertexId, '_c', new Date().toISOString());
+    this.localOps.push(op);
+    this.applyProperty(op);
+
+    return vertexId;
+  }
+
+  /** @returns The ID of the emitted move op */
+  private moveVertexInternal(vertexId: string, parentId: string | null): OpId {
+    this.undoGroup?.push({ type: 'move', vertexId, prevParentId: this.state.getVertex(vertexId)?.parentId });
+
+    this.lamportClock++;
     const op = newMoveVertexOp(this.lamportClock, this.peerId, vertexId, parentId);
     this.localOps.push(op);
     this.applyMove(op);
+    return op.id;
+  }
 
-    // Set the creation date
-    this.setVertexProperty(vertexId, '_c', new Date().toISOString());
+  private setVertexPropertyInternal(vertexId: string, key: string, value: VertexPropertyType | undefined) {
+    this.undoGroup?.push({ type: 'property', vertexId, key, prevValue: this.getVertexProperty(vertexId, key, false) });
 
-    return vertexId;
+    this.lamportClock++;
+    const op = newSetVertexPropertyOp(this.lamportClock, this.peerId, vertexId, key, value as VertexPropertyType);
+    this.localOps.push(op);
+    this.applyProperty(op);
+  }
+
+  /**
+   * Runs the function recording its local edits as a single undo step.
+   * Nested calls join the outermost group.
+   */
+  private withUndoGroup<T>(fn: () => T): T {
+    if (this.undoGroup || this.maxUndoSteps <= 0) {
+      return fn();
+    }
+
+    const group: UndoStep[] = [];
+    this.undoGroup = group;
+    try {
+      return fn();
+    } finally {
+      this.undoGroup = null;
+      if (group.length > 0) {
+        this.pushUndoGroup(group);
+        this.redoStack = [];
+      }
+    }
+  }
+
+  private checkMoveGuards(vertexId: string, parentId: string | null) {
+    // Guards only see moves under a parent; the root (null parent) is created once and never moved locally
+    if (parentId !== null && this.moveGuards.some(guard => !guard(vertexId, parentId))) {
+      throw new Error(`Moving vertex ${vertexId} under ${parentId} was rejected by a move guard`);
+    }
+  }
+
+  private pushUndoGroup(group: UndoStep[]) {
+    this.undoStack.push(group);
+    if (this.undoStack.length > this.maxUndoSteps) {
+      this.undoStack.splice(0, this.undoStack.length - this.maxUndoSteps);
+    }
+  }
+
+  /**
+   * Emits ops that revert the steps (in reverse order) and returns the steps that revert those ops in turn.
+   * Throws without emitting anything if a move guard rejects one of the moves.
+   */
+  private revertUndoSteps(steps: UndoStep[]): UndoStep[] {
+    for (const step of steps) {
+      if (step.type === 'move') {
+        this.checkMoveGuards(step.vertexId, step.prevParentId === undefined ? RepTree.NULL_VERTEX_ID : step.prevParentId);
+      }
+    }
+
+    const group: UndoStep[] = [];
+    this.undoGroup = group;
+    try {
+      for (let i = steps.length - 1; i >= 0; i--) {
+        const step = steps[i];
+        if (step.type === 'move') {
+          // Undoing a creation moves the vertex to the null vertex, same as deleting it
+          this.moveVertexInternal(step.vertexId, step.prevParentId === undefined ? RepTree.NULL_VERTEX_ID : step.prevParentId);
+        } else {
+          this.setVertexPropertyInternal(step.vertexId, step.key, step.prevValue);
+        }
+      }
+    } finally {
+      this.undoGroup = null;
+    }
+
+    return group;
+  }
+
+  private compareCreationOpIds(vertexIdA: string, vertexIdB: string): number {
+    const opIdA = this.propertiesAndTheirOpIds.get(`_c@${vertexIdA}`);
+    const opIdB = this.propertiesAndTheirOpIds.get(`_c@${vertexIdB}`);
+    if (opIdA && opIdB) {
+      return compareOpId(opIdA, opIdB);
+    }
+    return vertexIdA.localeCompare(vertexIdB);
   }
 
-  private newVertexInternalWithUUID(parentId: string | null): string {
-    const vertexId = uuid();
+  private newVertexInternalWithGeneratedId(parentId: string | null): string {
+    const vertexId = this.idGenerator();
+    // A custom generator could return a taken ID; creating it would silently move the existing vertex
+    if (this.state.getVertex(vertexId)) {
+      throw new Error(`Vertex ${vertexId} already exists`);
+    }
+    this.checkMoveGuards(vertexId, parentId);
     return this.newVertexInternal(vertexId, parentId);
   }
 
@@ -588,19 +1152,47 @@ export class RepTree {
     }
   }
 
-  private applyPendingMovesForParent(parentId: string) {
-    // If a parent doesn't exist, we can't apply pending moves yet.
-    if (!this.state.getVertex(parentId)) {
+  /**
+   * Stashes the op until what it waits for arrives.
+   * The same op can be received more than once before that (e.g. re-delivered during sync), so it's stashed only once.
+   * Otherwise it would be applied twice and end up duplicated in the op log.
+   */
+  private stashPendingOp<T extends VertexOperation>(pending: Map<string, T[]>, key: string, op: T) {
+    const opId = opIdToString(op.id);
+    if (this.pendingOpIds.has(opId)) {
       return;
     }
+    this.pendingOpIds.add(opId);
 
-    const pendingMoves = this.pendingMovesWithMissingParent.get(parentId);
-    if (!pendingMoves) {
-      return;
+    const ops = pending.get(key);
+    if (!ops) {
+      pending.set(key, [op]);
+    } else {
+      ops.push(op);
+    }
+  }
+
+  /** Removes the ops stashed under the key from the pending buffer and returns them */
+  private takePendingOps<T extends VertexOperation>(pending: Map<string, T[]>, key: string): T[] {
+    const ops = pending.get(key);
+    if (!ops) {
+      return [];
     }
 
-    this.pendingMovesWithMissingParent.delete(parentId);
+    pending.delete(key);
+    for (const op of ops) {
+      this.pendingOpIds.delete(opIdToString(op.id));
+    }
+    return ops;
+  }
+
+  private applyPendingMovesForParent(parentId: string) {
+    // If a parent doesn't exist, we can't apply pending moves yet.
+    if (!this.state.getVertex(parentId)) {
+      return;
+    }
 
+    const pendingMoves = this.takePendingOps(this.pendingMovesWithMissingParent, parentId);
     for (const pendingOp of pendingMoves) {
       this.applyMove(pendingOp);
     }
@@ -610,10 +1202,7 @@ export class RepTree {
     // Check if a parent (unless we're dealing with the root vertex) exists for the move operation.
     // If it doesn't exist, stash the move op for later
     if (op.parentId !== null && !this.state.getVertex(op.parentId)) {
-      if (!this.pendingMovesWithMissingParent.has(op.parentId)) {
-        this.pendingMovesWithMissingParent.set(op.parentId, []);
-      }
-      this.pendingMovesWithMissingParent.get(op.parentId)!.push(op);
+      this.stashPendingOp(this.pendingMovesWithMissingParent, op.parentId, op);
       return;
     }
 
@@ -686,10 +1275,7 @@ export class RepTree {
 
       // If the vertex doesn't exist, we will wait for the move operation to appear that will create the vertex
       // so we can apply the property then.
-      if (!this.pendingPropertiesWithMissingVertex.has(op.targetId)) {
-        this.pendingPropertiesWithMissingVertex.set(op.targetId, []);
-      }
-      this.pendingPropertiesWithMissingVertex.get(op.targetId)!.push(op);
+      this.stashPendingOp(this.pendingPropertiesWithMissingVertex, op.targetId, op);
       return;
     }
 
@@ -712,7 +1298,12 @@ export class RepTree {
       } else {
         // We add it to set of known ops to avoid adding them to `setPropertyOps` multiple times 
         // if we ever receive the same op from another peer.
-        this.knownOps.add(opIdToString(op.id));
+        this.markOpAsKnown(op);
+        // The op lost to a newer one but we still have it, so it belongs to the state vector.
+        // Otherwise the state vector would depend on the order the ops arrived in.
+        if (this._stateVectorEnabled) {
+          this.stateVector.updateFromOp(op);
+        }
       }
 
       // Remove the transient property if the current op is greater
@@ -730,15 +1321,71 @@ export class RepTree {
   }
 
   private applyOperation(op: VertexOperation) {
+    if (this.applyOperationIfDepsAreKnown(op)) {
+      this.applyPendingOpsWithSatisfiedDeps();
+    }
+  }
+
+  /** @returns false if the op was stashed because it depends on ops that we haven't applied yet */
+  private applyOperationIfDepsAreKnown(op: VertexOperation): boolean {
+    const missingDepId = this.findMissingDepId(op);
+    if (missingDepId) {
+      this.stashPendingOp(this.pendingOpsWithMissingDeps, missingDepId, op);
+      return false;
+    }
+
     if (isMoveVertexOp(op)) {
       this.applyMove(op);
     } else if (isAnyPropertyOp(op)) {
       this.applyProperty(op);
     }
+    return true;
+  }
+
+  private findMissingDepId(op: VertexOperation): string | undefined {
+    if (!op.deps) {
+      return undefined;
+    }
+
+    for (const dep of op.deps) {
+      const depId = opIdToString(dep);
+      if (!this.knownOps.has(depId)) {
+        return depId;
+      }
+    }
+
+    return undefined;
+  }
+
+  /**
+   * Applies the stashed ops whose dependencies got applied. Works through `satisfiedDepIds` as a worklist
+   * instead of recursing, so a long chain of dependent ops arriving in reverse order is applied in linear time
+   * without growing the stack.
+   */
+  private applyPendingOpsWithSatisfiedDeps() {
+    while (this.satisfiedDepIds.length > 0) {
+      const depId = this.satisfiedDepIds.pop()!;
+      const pendingOps = this.takePendingOps(this.pendingOpsWithMissingDeps, depId);
+      for (const pendingOp of pendingOps) {
+        // Skip ops that got applied in the meantime
+        if (!this.knownOps.has(opIdToString(pendingOp.id))) {
+          // Ops it unblocks are queued in `satisfiedDepIds` as it's applied
+          this.applyOperationIfDepsAreKnown(pendingOp);
+        }
+      }
+    }
+  }
+
+  private markOpAsKnown(op: VertexOperation) {
+    const opId = opIdToString(op.id);
+    this.knownOps.add(opId);
+    if (this.pendingOpsWithMissingDeps.has(opId)) {
+      this.satisfiedDepIds.push(opId);
+    }
   }
 
   private reportOpAsApplied(op: VertexOperation) {
-    this.knownOps.add(opIdToString(op.id));
+    this.markOpAsKnown(op);
 
     if (this._stateVectorEnabled) {
       this.stateVector.updateFromOp(op);
@@ -769,8 +1416,7 @@ export class RepTree {
     // If the vertex didn't exist before the move - see if it has pending properties
     // and apply them.
     if (!targetVertex) {
-      const pendingProperties = this.pendingPropertiesWithMissingVertex.get(op.targetId) || [];
-      this.pendingPropertiesWithMissingVertex.delete(op.targetId);
+      const pendingProperties = this.takePendingOps(this.pendingPropertiesWithMissingVertex, op.targetId);
       for (const prop of pendingProperties) {
         this.applyProperty(prop);
       }
@@ -809,20 +1455,17 @@ export class RepTree {
    * Determines which operations are needed to synchronize 
    * with the provided state vector.
    * 
-   * @param theirStateVector The state vector from another peer
+   * @param theirStateVector The state vector from another peer, as ranges or encoded with `StateVector.encode`
    * @returns Operations that should be sent to the other peer, sorted by OpId.
    */
-  getMissingOps(theirStateVector: Record<string, number[][]>): VertexOperation[] {
+  getMissingOps(theirStateVector: Record<string, number[][]> | Uint8Array): VertexOperation[] {
     // If state vector is disabled, fallback to sending all ops
     if (!this._stateVectorEnabled) {
       return [...this.moveOps, ...this.setPropertyOps];
     }
 
-    // Create a StateVector instance from their state vector
-    const otherStateVector = new StateVector(theirStateVector);
-
     // Get the missing ranges
-    const missingRanges = this.stateVector.diff(otherStateVector);
+    const missingRanges = this.getMissingRanges(theirStateVector)!;
 
     // Then, retrieve only the operations that fall within those ranges
     const missingOps: VertexOperation[] = [];
@@ -847,6 +1490,92 @@ export class RepTree {
     return missingOps;
   }
 
+  /**
+   * Returns the ranges of operations (per peer) that we have and the other peer doesn't.
+   * These are the ranges `getMissingOps` uses to pick the ops, handy for debugging sync.
+   * Returns null if state vector tracking is disabled.
+   *
+   * @param theirStateVector The state vector from another peer, as ranges or encoded with `StateVector.encode`
+   */
+  getMissingRanges(theirStateVector: Record<string, number[][]> | Uint8Array): OpIdRange[] | null {
+    if (!this._stateVectorEnabled) {
+      return null;
+    }
+
+    const theirs = theirStateVector instanceof Uint8Array
+      ? StateVector.decode(theirStateVector)
+      : new StateVector(theirStateVector);
+    return this.stateVector.diff(theirs);
+  }
+
+  /**
+   * Same as `getMissingOps` but returns at most `maxOps` operations at a time, so a peer that is far behind
+   * can be synced incrementally.
+   * Pass the returned cursor as `theirStateVector` to get the next page; an empty page means they're up to date.
+   * Pages follow the order of the op logs (moves first), so an op can arrive before the ops it refers to;
+   * the receiving tree stashes such ops until the rest arrives.
+   *
+   * @param theirStateVector The state vector of the other peer or a cursor from the previous page
+   * @param maxOps The maximum number of operations in the page
+   * @returns The operations of the page (sorted by OpId) and the state vector of the other peer after applying them
+   */
+  getMissingOpsPaged(theirStateVector: Record<string, number[][]>, maxOps: number): { ops: VertexOperation[], cursor: Record<string, number[][]> } {
+    if (!Number.isInteger(maxOps) || maxOps < 1) {
+      throw new Error(`maxOps must be a positive integer, got ${maxOps}`);
+    }
+
+    const cursor = new StateVector(theirStateVector);
+
+    // Walk the op logs directly and stop once the page is full, so a page doesn't cost building the whole missing set
+    const ops: VertexOperation[] = [];
+    for (const log of [this.moveOps, this.setPropertyOps]) {
+      for (const op of log) {
+        if (ops.length >= maxOps) break;
+        if (!cursor.contains(op.id)) {
+          ops.push(op);
+        }
+      }
+    }
+    ops.sort((a, b) => compareOpId(a.id, b.id));
+
+    for (const op of ops) {
+      cursor.updateFromOp(op);
+    }
+
+    return { ops, cursor: cursor.getState() };
+  }
+
+  /**
+   * Pulls the operations we're missing from another tree without sending ours back.
+   * It's the one-way counterpart of a full sync, e.g. for a read replica following a primary tree.
+   *
+   * @param other The tree to pull the operations from
+   * @returns The number of operations received from the other tree
+   */
+  pullFrom(other: RepTree): number {
+    const ops = other.getMissingOps(this.getStateVector() ?? {});
+    this.merge(ops);
+    return ops.length;
+  }
+
+  /**
+   * Syncs with another tree in both directions, so both trees end up with the same operations.
+   * Missing operations are collected from both state vectors before anything is applied,
+   * so the operations received from the other tree are not sent back to it.
+   *
+   * @param other The tree to sync with
+   * @returns The number of operations sent to the other tree and received from it
+   */
+  syncWith(other: RepTree): { sent: number, received: number } {
+    const opsToSend = this.getMissingOps(other.getStateVector() ?? {});
+    const opsToReceive = other.getMissingOps(this.getStateVector() ?? {});
+
+    other.merge(opsToSend);
+    this.merge(opsToReceive);
+
+    return { sent: opsToSend.length, received: opsToReceive.length };
+  }
+
   /**
    * Gets or sets whether state vector tracking is enabled
    */
@@ -1,5 +1,5 @@
 import { OpId } from "./OpId";
-import type { OpIdRange } from "./treeTypes";
+import type { OpIdRange, StateVectorDiffSummary } from "./treeTypes";
 import type { VertexOperation } from "./operations";
 
 /**
@@ -60,6 +60,35 @@ export function subtractRanges(rangesA: number[][], rangesB: number[][]): number
   return result;
 }
 
+/** Writes a non-negative integer as an unsigned LEB128 varint. Uses arithmetic instead of bitwise ops to support counters above 2^32. */
+function writeVarint(bytes: number[], value: number): void {
+  while (value >= 0x80) {
+    bytes.push((value % 0x80) | 0x80);
+    value = Math.floor(value / 0x80);
+  }
+  bytes.push(value);
+}
+
+/** Reads an unsigned LEB128 varint, returns the value and the position after it */
+function readVarint(bytes: Uint8Array, pos: number): [number, number] {
+  let value = 0;
+  let multiplier = 1;
+  while (true) {
+    if (pos >= bytes.length) {
+      throw new Error('Invalid encoded state vector: unexpected end of data');
+    }
+    const byte = bytes[pos++];
+    value += (byte & 0x7f) * multiplier;
+    if ((byte & 0x80) === 0) {
+      return [value, pos];
+    }
+    multiplier *= 0x80;
+    if (multiplier > Number.MAX_SAFE_INTEGER) {
+      throw new Error('Invalid encoded state vector: varint is too long');
+    }
+  }
+}
+
 /**
  * StateVector tracks operations that have been applied using a range-based representation.
  * It's used for synchronization between peers to determine which operations need to be sent.
@@ -214,6 +243,24 @@ export class StateVector {
     return missingRanges;
   }
 
+  /**
+   * Counts the operations we have that the other state vector is missing, without looking at the operations themselves.
+   * Cheap enough to drive progress bars or to decide between sending a snapshot and syncing incrementally.
+   *
+   * @param other The other state vector to compare against
+   */
+  diffSummary(other: StateVector): StateVectorDiffSummary {
+    const summary: StateVectorDiffSummary = { total: 0, byPeer: {} };
+
+    for (const { peerId, start, end } of this.diff(other)) {
+      const count = end - start + 1;
+      summary.byPeer[peerId] = (summary.byPeer[peerId] ?? 0) + count;
+      summary.total += count;
+    }
+
+    return summary;
+  }
+
   /**
    * Checks if the state vector contains the given operation ID
    * 
@@ -237,6 +284,86 @@ export class StateVector {
     return false;
   }
 
+  /**
+   * Encodes the state vector in a compact binary form, far smaller than JSON when there are many peers.
+   * Layout (all numbers are varints): peer count, then for each peer the byte length of its UTF-8 ID, the ID,
+   * the range count and for each range its start as a delta from the previous range's end and its length minus one.
+   * Expects sorted, non-overlapping ranges (what `update` maintains).
+   */
+  encode(): Uint8Array {
+    const bytes: number[] = [];
+    const textEncoder = new TextEncoder();
+    const peers = Object.entries(this.ranges);
+
+    writeVarint(bytes, peers.length);
+    for (const [peerId, peerRanges] of peers) {
+      const peerIdBytes = textEncoder.encode(peerId);
+      writeVarint(bytes, peerIdBytes.length);
+      bytes.push(...peerIdBytes);
+
+      writeVarint(bytes, peerRanges.length);
+      let prevEnd = 0;
+      for (let i = 0; i < peerRanges.length; i++) {
+        const [start, end] = peerRanges[i];
+        const isValid = Number.isInteger(start) && Number.isInteger(end) && end >= start &&
+          (i === 0 ? start >= 0 : start > prevEnd);
+        if (!isValid) {
+          throw new Error(`Can't encode unsorted or overlapping ranges of peer ${peerId}`);
+        }
+        writeVarint(bytes, start - prevEnd);
+        writeVarint(bytes, end - start);
+        prevEnd = end;
+      }
+    }
+
+    return new Uint8Array(bytes);
+  }
+
+  /**
+   * Decodes a state vector produced by `encode`
+   * @param bytes The encoded state vector
+   * @returns A new StateVector instance
+   */
+  static decode(bytes: Uint8Array): StateVector {
+    const textDecoder = new TextDecoder('utf-8', { fatal: true });
+    const state: Record<string, number[][]> = {};
+    let pos = 0;
+    let peerCount: number;
+
+    [peerCount, pos] = readVarint(bytes, pos);
+    for (let i = 0; i < peerCount; i++) {
+      let peerIdLength: number;
+      [peerIdLength, pos] = readVarint(bytes, pos);
+      if (pos + peerIdLength > bytes.length) {
+        throw new Error('Invalid encoded state vector: unexpected end of data');
+      }
+      const peerId = textDecoder.decode(bytes.subarray(pos, pos + peerIdLength));
+      pos += peerIdLength;
+
+      let rangeCount: number;
+      [rangeCount, pos] = readVarint(bytes, pos);
+      const peerRanges: number[][] = [];
+      let prevEnd = 0;
+      for (let j = 0; j < rangeCount; j++) {
+        let startDelta: number;
+        let lengthMinusOne: number;
+        [startDelta, pos] = readVarint(bytes, pos);
+        [lengthMinusOne, pos] = readVarint(bytes, pos);
+        const start = prevEnd + startDelta;
+        const end = start + lengthMinusOne;
+        peerRanges.push([start, end]);
+        prevEnd = end;
+      }
+      state[peerId] = peerRanges;
+    }
+
+    if (pos !== bytes.length) {
+      throw new Error('Invalid encoded state vector: unexpected trailing data');
+    }
+
+    return new StateVector(state);
+  }
+
   /**
    * Creates a copy of this state vector
    */
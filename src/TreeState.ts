@@ -5,12 +5,17 @@ export class TreeState {
   private vertices: Map<TreeVertexId, VertexState>;
   private changeCallbacks: Map<TreeVertexId, Set<(events: VertexChangeEvent[]) => void>> = new Map();
   private globalChangeCallbacks: Set<(events: VertexChangeEvent[]) => void> = new Set();
+  private compareChildrenTies: (a: VertexState, b: VertexState) => number;
 
   private batchTickInterval: NodeJS.Timeout;
   private batchedEvents: Map<TreeVertexId, VertexChangeEvent[]> = new Map();
 
-  constructor() {
+  /**
+   * @param compareChildrenTies - Orders children created at the same time, compares IDs by default
+   */
+  constructor(compareChildrenTies: (a: VertexState, b: VertexState) => number = (a, b) => a.id.localeCompare(b.id)) {
     this.vertices = new Map();
+    this.compareChildrenTies = compareChildrenTies;
 
     this.batchTickInterval = setInterval(() => {
       this.processBatchedEvents();
@@ -76,9 +81,11 @@ export class TreeState {
       .sort((a, b) => {
         const aDate = a.getProperty('_c') as string;
         const bDate = b.getProperty('_c') as string;
-        if (!aDate) return -1;
-        if (!bDate) return 1;
-        return new Date(aDate).getTime() - new Date(bDate).getTime();
+        if (aDate && !bDate) return 1;
+        if (!aDate && bDate) return -1;
+        const byDate = aDate && bDate ? new Date(aDate).getTime() - new Date(bDate).getTime() : 0;
+        // Break ties so every replica lists the children in the same order
+        return byDate !== 0 ? byDate : this.compareChildrenTies(a, b);
       }) as VertexState[];
   }
 
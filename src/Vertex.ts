@@ -74,8 +74,8 @@ export class Vertex {
     return this.tree.getChildrenIds(this.id);
   }
 
-  /** Returns the ancestors of this vertex. The first element is the root vertex.
-   * E.g root -> grandparent -> parent.
+  /** Returns the ancestors of this vertex. The first element is the parent, the last one is the root vertex.
+   * E.g parent -> grandparent -> root.
    * Doesn't include this vertex in the array.
    */
   get ancestors(): Vertex[] {
@@ -125,6 +125,15 @@ export class Vertex {
     this.tree.setVertexProperty(this.id, key, value);
   }
 
+  /** Removes a property from this vertex. */
+  unsetProperty(key: string): void {
+    if (this.getProperty(key, false) === undefined) {
+      return;
+    }
+
+    this.tree.unsetVertexProperty(this.id, key);
+  }
+
   /** Sets a transient property on this vertex. Transient properties are not persisted to the tree and are not included in the state vector. */
   setTransientProperty(key: string, value: VertexPropertyType): void {
     // First check if the property is already set
@@ -5,6 +5,8 @@ export interface MoveVertex {
   id: OpId;
   targetId: string;
   parentId: string | null;
+  /** Ops that have to be applied before this one. Merged ops wait in a buffer until all of them arrive. */
+  deps?: OpId[];
 }
 
 export interface SetVertexProperty {
@@ -13,6 +15,8 @@ export interface SetVertexProperty {
   key: string;
   value: VertexPropertyType;
   transient: boolean;
+  /** Ops that have to be applied before this one. Merged ops wait in a buffer until all of them arrive. */
+  deps?: OpId[];
 }
 
 export type VertexOperation = MoveVertex | SetVertexProperty;
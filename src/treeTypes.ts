@@ -13,6 +13,13 @@ export type TreeVertexProperty = {
   readonly value: VertexPropertyType;
 }
 
+/** A vertex with its properties and all of its descendants as a plain JSON object */
+export type VertexJson = {
+  id: TreeVertexId;
+  properties: Record<string, JsonValue>;
+  children: VertexJson[];
+}
+
 type VertexChangeEventType = 'move' | 'property' | 'children';
 
 export interface VertexChangeEvent {
@@ -46,3 +53,29 @@ export interface OpIdRange {
   end: number;
 }
 
+/**
+ * How many operations one state vector has that another one doesn't
+ */
+export interface StateVectorDiffSummary {
+  /** Missing operations in total */
+  total: number;
+  /** Missing operations per peer, only peers with missing operations are included */
+  byPeer: Record<string, number>;
+}
+
+/**
+ * Problems found by `RepTree.checkIntegrity`.
+ * Only `cycles` and `orphans` indicate a corrupted state; both are always empty for a healthy tree.
+ */
+export interface IntegrityReport {
+  /** Each cycle as the IDs of the vertices forming it. Non-empty only if the state is corrupted. */
+  cycles: TreeVertexId[][];
+  /** Vertices whose parent doesn't exist. Non-empty only if the state is corrupted. */
+  orphans: TreeVertexId[];
+  /**
+   * Vertices that ops reference (as a parent or a property target) but that don't exist yet.
+   * Those ops wait in the pending buffers, so a healthy replica can have some in the middle of a sync;
+   * the list empties once the missing ops arrive.
+   */
+  missingVertices: TreeVertexId[];
+}
@@ -7,7 +7,8 @@ export default function isJsonValue(v: any): boolean {
   if (v === undefined) return true; // deletion signal
   if (v === null) return true;
   const t = typeof v;
-  if (t === 'string' || t === 'number' || t === 'boolean') return true;
+  if (t === 'number') return Number.isFinite(v); // NaN and Infinity would turn into null in JSON
+  if (t === 'string' || t === 'boolean') return true;
   if (t === 'bigint' || t === 'function' || t === 'symbol') return false;
   if (Array.isArray(v)) return v.every(isJsonValue);
   if (t === 'object') {